@@ -0,0 +1,94 @@
+//! Compares the spatial index backends at several enemy counts: bringing the
+//! store up to date after every enemy moved one tick, and the queries the
+//! wand and player contact run each tick.
+//!
+//! `cargo bench --bench spatial_index`
+
+use bevy::prelude::*;
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use eternal_gauntlet::spatial_index::{EnemyKdTree, SpatialHash, SpatialStore};
+use eternal_gauntlet::{ENEMY_SPEED, SPATIAL_HASH_CELL_SIZE};
+
+const ENEMY_COUNTS: [usize; 3] = [1_000, 5_000, 20_000];
+const FIELD_RADIUS: f32 = 5000.0;
+const TICK: f32 = 1.0 / 60.0;
+
+fn enemies(count: usize) -> Vec<(Entity, Vec2)> {
+    let mut rng = ChaCha8Rng::seed_from_u64(0);
+    (0..count)
+        .map(|index| {
+            let position = Vec2::new(
+                rng.gen_range(-FIELD_RADIUS..FIELD_RADIUS),
+                rng.gen_range(-FIELD_RADIUS..FIELD_RADIUS),
+            );
+            (Entity::from_raw(index as u32), position)
+        })
+        .collect()
+}
+
+/// Every enemy takes one step towards the player at the origin.
+fn step(enemies: &mut [(Entity, Vec2)]) {
+    for (_, position) in enemies.iter_mut() {
+        *position -= position.normalize_or_zero() * ENEMY_SPEED * TICK;
+    }
+}
+
+fn queries(store: &impl SpatialStore) -> usize {
+    store.within_radius(Vec2::ZERO, 50.0).len()
+        + store.within_radius(Vec2::new(100.0, 0.0), 300.0).len()
+        + store.within_radius(Vec2::new(200.0, 50.0), 150.0).len()
+        + store.nearest(Vec2::ZERO, 1).len()
+}
+
+fn update(c: &mut Criterion) {
+    let mut group = c.benchmark_group("update");
+    for count in ENEMY_COUNTS {
+        let mut moved = enemies(count);
+        step(&mut moved);
+
+        group.bench_with_input(
+            BenchmarkId::new("kd_tree_rebuild", count),
+            &moved,
+            |b, moved| b.iter(|| EnemyKdTree::build(black_box(moved.iter().copied()))),
+        );
+
+        // Alternate between the two frames so every iteration moves every enemy
+        let start = enemies(count);
+        let mut hash = SpatialHash::new(SPATIAL_HASH_CELL_SIZE);
+        let mut frames = [&start, &moved].into_iter().cycle();
+        group.bench_function(BenchmarkId::new("hash_incremental", count), |b| {
+            b.iter(|| {
+                for (entity, position) in frames.next().unwrap().iter() {
+                    hash.insert(*entity, black_box(*position));
+                }
+            })
+        });
+    }
+    group.finish();
+}
+
+fn query(c: &mut Criterion) {
+    let mut group = c.benchmark_group("query");
+    for count in ENEMY_COUNTS {
+        let enemies = enemies(count);
+        let tree = EnemyKdTree::build(enemies.iter().copied());
+        let mut hash = SpatialHash::new(SPATIAL_HASH_CELL_SIZE);
+        for (entity, position) in enemies.iter() {
+            hash.insert(*entity, *position);
+        }
+
+        group.bench_function(BenchmarkId::new("kd_tree", count), |b| {
+            b.iter(|| queries(black_box(&tree)))
+        });
+        group.bench_function(BenchmarkId::new("hash", count), |b| {
+            b.iter(|| queries(black_box(&hash)))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, update, query);
+criterion_main!(benches);
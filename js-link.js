@@ -1,4 +1,5 @@
-export function send_to_js(score) {
-  console.log("score sent to js", score);
-  window.postMessage({ type: "score", score }, "*");
+export function send_to_js(payload) {
+  const message = JSON.parse(payload);
+  console.log("message sent to js", message);
+  window.postMessage(message, "*");
 }
@@ -1,116 +1,273 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
 use bevy::prelude::*;
+use serde::Deserialize;
 
 use crate::{
     enemy::Enemy,
-    lightning_hit_bundle::LightningHit,
-    player::{Player, PlayerState},
+    generate_texture_atlas::{ClipError, SerializableTextureAtlasLayout},
+    player::{Player, PlayerInput, PlayerState},
     state::GameState,
     wand::Wand,
-    CursorPosition,
 };
 
-#[derive(Component)]
+/// First and last atlas index of a clip's frames.
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct AnimationIndices {
     pub first: usize,
     pub last: usize,
 }
 
-pub struct AnimationPlugin;
+/// What a clip does after its last frame.
+#[derive(Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PlayMode {
+    #[default]
+    Loop,
+    /// Stops on the last frame and sends `AnimationFinished`.
+    Once,
+    /// Plays back and forth.
+    PingPong,
+}
+
+/// A clip as written in the data files.
+#[derive(Deserialize, Debug, Clone)]
+pub struct ClipDefinition {
+    /// Clip name in the atlas layout, e.g. `undead/zombie`.
+    pub frames: String,
+    pub fps: f32,
+    #[serde(default)]
+    pub mode: PlayMode,
+}
+
+/// A clip resolved against its atlas layout.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationClip {
+    pub name: String,
+    pub frames: AnimationIndices,
+    pub fps: f32,
+    pub mode: PlayMode,
+}
+
+/// Plays an `AnimationClip` on the entity's `TextureAtlas`.
+#[derive(Component, Debug, Clone)]
+pub struct AnimationPlayer {
+    clip: AnimationClip,
+    frame: usize,
+    reverse: bool,
+    finished: bool,
+    timer: Timer,
+    next: Option<AnimationClip>,
+}
+
+/// Clips an entity can play, by name. An `AnimationState` component picks
+/// which one plays, e.g. `idle` and `run` for heroes.
+#[derive(Component, Debug, Clone, Default)]
+pub struct AnimationClips(pub HashMap<String, AnimationClip>);
+
+/// A state component that selects a clip from the entity's `AnimationClips`.
+/// States without a clip keep playing the current one.
+pub trait AnimationState: Component {
+    fn clip_name(&self) -> &str;
+}
+
+/// Despawns the entity once its clip finishes.
+#[derive(Component)]
+pub struct DespawnOnFinish;
 
-#[derive(Component, Deref, DerefMut)]
-pub struct AnimationTimer(pub Timer);
+/// Sent when a `PlayMode::Once` clip plays its last frame.
+#[derive(Event, Debug, Clone)]
+pub struct AnimationFinished {
+    pub entity: Entity,
+    pub clip: String,
+}
+
+pub struct AnimationPlugin;
 
 impl Plugin for AnimationPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            Update,
-            (
-                animation_timer_tick,
-                animate_player,
-                animate_enemy,
-                animate_hit,
-                flip_wand_sprite_y,
-                flip_player_sprite_x,
-                flip_enemy_sprite_x,
+        app.add_event::<AnimationFinished>()
+            .add_systems(
+                Update,
+                (
+                    play_state_clips::<PlayerState>,
+                    play_animations,
+                    despawn_finished_animations,
+                )
+                    .chain()
+                    .run_if(in_state(GameState::InGame).or_else(in_state(GameState::MainMenu))),
             )
-                .run_if(in_state(GameState::InGame)),
-        );
+            .add_systems(
+                Update,
+                (
+                    flip_wand_sprite_y,
+                    flip_player_sprite_x,
+                    flip_enemy_sprite_x,
+                )
+                    .run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
-fn animation_timer_tick(
-    time: Res<Time>,
-    mut query: Query<&mut AnimationTimer, With<AnimationTimer>>,
-) {
-    for mut timer in query.iter_mut() {
-        timer.tick(time.delta());
+impl ClipDefinition {
+    pub fn resolve(
+        &self,
+        layout: &SerializableTextureAtlasLayout,
+    ) -> Result<AnimationClip, ClipError> {
+        if !self.fps.is_finite() || self.fps <= 0.0 {
+            return Err(ClipError::BadFrameRate(self.frames.clone()));
+        }
+        Ok(AnimationClip {
+            name: self.frames.clone(),
+            frames: layout.clip(&self.frames)?,
+            fps: self.fps,
+            mode: self.mode,
+        })
     }
 }
 
-fn animate_player(
-    mut player_query: Query<(&mut TextureAtlas, &PlayerState, &AnimationTimer), With<Player>>,
-) {
-    if player_query.is_empty() {
-        return;
+impl AnimationPlayer {
+    pub fn new(clip: AnimationClip) -> Self {
+        let timer = Timer::from_seconds(1.0 / clip.fps, TimerMode::Repeating);
+        Self {
+            clip,
+            frame: 0,
+            reverse: false,
+            finished: false,
+            timer,
+            next: None,
+        }
+    }
+
+    /// Plays `next` when this player's `PlayMode::Once` clip finishes.
+    pub fn then(mut self, next: AnimationClip) -> Self {
+        self.next = Some(next);
+        self
+    }
+
+    /// Switches to `clip` from its first frame, unless it is already playing.
+    pub fn play(&mut self, clip: &AnimationClip) {
+        if self.clip.name != clip.name {
+            *self = Self::new(clip.clone());
+        }
     }
 
-    let (mut atlas, state, timer) = player_query.single_mut();
-    if timer.just_finished() {
-        let base_sprite_index = match state {
-            PlayerState::Idle => 0,
-            PlayerState::Run => 4,
-        };
-        atlas.index = base_sprite_index + (atlas.index + 1) % 4;
+    pub fn clip(&self) -> &AnimationClip {
+        &self.clip
+    }
+
+    /// Atlas index of the current frame.
+    pub fn index(&self) -> usize {
+        self.clip.frames.first + self.frame
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Advances the clip by `delta` and returns the name of the clip that
+    /// finished during it, if any.
+    pub fn tick(&mut self, delta: Duration) -> Option<String> {
+        self.timer.tick(delta);
+        let mut finished = None;
+        for _ in 0..self.timer.times_finished_this_tick() {
+            if self.finished {
+                break;
+            }
+            if self.step() {
+                finished = Some(self.clip.name.clone());
+                match self.next.take() {
+                    Some(next) => *self = Self::new(next),
+                    None => self.finished = true,
+                }
+            }
+        }
+        finished
+    }
+
+    /// Moves to the next frame, returning whether a `PlayMode::Once` clip ended.
+    fn step(&mut self) -> bool {
+        let last = self.clip.frames.last - self.clip.frames.first;
+        match self.clip.mode {
+            PlayMode::Loop => {
+                self.frame = if self.frame >= last {
+                    0
+                } else {
+                    self.frame + 1
+                }
+            }
+            PlayMode::Once => {
+                if self.frame >= last {
+                    return true;
+                }
+                self.frame += 1;
+            }
+            PlayMode::PingPong => {
+                if last == 0 {
+                    return false;
+                }
+                if self.frame >= last {
+                    self.reverse = true;
+                } else if self.frame == 0 {
+                    self.reverse = false;
+                }
+                self.frame = if self.reverse {
+                    self.frame - 1
+                } else {
+                    self.frame + 1
+                };
+            }
+        }
+        false
     }
 }
 
-fn animate_enemy(
-    mut enemy_query: Query<(&mut TextureAtlas, &AnimationTimer, &AnimationIndices), With<Enemy>>,
+fn play_state_clips<S: AnimationState>(
+    mut query: Query<(&S, &AnimationClips, &mut AnimationPlayer)>,
 ) {
-    if enemy_query.is_empty() {
-        return;
+    for (state, clips, mut player) in query.iter_mut() {
+        if let Some(clip) = clips.0.get(state.clip_name()) {
+            if player.clip().name != clip.name {
+                player.play(clip);
+            }
+        }
     }
+}
 
-    for (mut atlas, timer, animation_indicies) in enemy_query.iter_mut() {
-        if timer.just_finished() {
-            atlas.index = if atlas.index == animation_indicies.last {
-                animation_indicies.first
-            } else {
-                atlas.index + 1
-            };
+fn play_animations(
+    time: Res<Time>,
+    mut query: Query<(Entity, &mut AnimationPlayer, &mut TextureAtlas)>,
+    mut finished_events: EventWriter<AnimationFinished>,
+) {
+    for (entity, mut player, mut atlas) in query.iter_mut() {
+        if let Some(clip) = player.tick(time.delta()) {
+            finished_events.send(AnimationFinished { entity, clip });
+        }
+        if atlas.index != player.index() {
+            atlas.index = player.index();
         }
     }
 }
 
-fn animate_hit(
+fn despawn_finished_animations(
     mut commands: Commands,
-    mut hit_query: Query<
-        (
-            &mut TextureAtlas,
-            &AnimationTimer,
-            &AnimationIndices,
-            Entity,
-        ),
-        With<LightningHit>,
-    >,
+    mut finished_events: EventReader<AnimationFinished>,
+    query: Query<&AnimationPlayer, With<DespawnOnFinish>>,
 ) {
-    if hit_query.is_empty() {
-        return;
-    }
-
-    for (mut atlas, timer, animation_indicies, entity) in hit_query.iter_mut() {
-        if timer.just_finished() {
-            if atlas.index == animation_indicies.last {
-                commands.entity(entity).despawn();
-            } else {
-                atlas.index = atlas.index + 1;
-            }
+    for event in finished_events.read() {
+        if query
+            .get(event.entity)
+            .is_ok_and(|player| player.is_finished())
+        {
+            commands.entity(event.entity).despawn();
         }
     }
 }
 
+/// Faces the aim of the current input, so replays face where they were recorded aiming.
 fn flip_player_sprite_x(
-    cursor_position: Res<CursorPosition>,
+    input: Res<PlayerInput>,
     mut player_query: Query<(&mut Sprite, &Transform), With<Player>>,
 ) {
     if player_query.is_empty() {
@@ -118,12 +275,8 @@ fn flip_player_sprite_x(
     }
 
     let (mut sprite, transform) = player_query.single_mut();
-    if let Some(cursor_position) = cursor_position.0 {
-        if cursor_position.x > transform.translation.x {
-            sprite.flip_x = false;
-        } else {
-            sprite.flip_x = true;
-        }
+    if let Some(aim) = input.aim {
+        sprite.flip_x = aim.x <= transform.translation.x;
     }
 }
 
@@ -137,16 +290,12 @@ fn flip_enemy_sprite_x(
 
     let player_pos = player_query.single().translation;
     for (mut sprite, transform) in enemy_query.iter_mut() {
-        if transform.translation.x < player_pos.x {
-            sprite.flip_x = false;
-        } else {
-            sprite.flip_x = true;
-        }
+        sprite.flip_x = transform.translation.x >= player_pos.x;
     }
 }
 
 fn flip_wand_sprite_y(
-    cursor_position: Res<CursorPosition>,
+    input: Res<PlayerInput>,
     mut wand_query: Query<(&mut Sprite, &Transform), With<Wand>>,
 ) {
     if wand_query.is_empty() {
@@ -154,11 +303,7 @@ fn flip_wand_sprite_y(
     }
 
     let (mut sprite, transform) = wand_query.single_mut();
-    if let Some(cursor_position) = cursor_position.0 {
-        if cursor_position.x > transform.translation.x {
-            sprite.flip_y = false;
-        } else {
-            sprite.flip_y = true;
-        }
+    if let Some(aim) = input.aim {
+        sprite.flip_y = aim.x <= transform.translation.x;
     }
 }
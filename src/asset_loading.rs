@@ -1,5 +1,6 @@
 use crate::enemy::{EnemiesDataHandle, EnemiesSpawnDataHandle};
 use crate::enemy_textures::EnemyTextureAtlasHandle;
+use crate::hero_registry::{HeroManifestHandle, HeroTextureAtlasHandle};
 use crate::hit_textures::HitTextureAtlasHandle;
 use crate::state::GameState;
 use bevy::asset::LoadState;
@@ -19,6 +20,10 @@ fn check_assets(
     hit_texture_atlas_handle: Res<HitTextureAtlasHandle>,
     enemies_data_handle: Res<EnemiesDataHandle>,
     enemies_spawn_data_handle: Res<EnemiesSpawnDataHandle>,
+    (hero_manifest_handle, hero_texture_atlas_handle): (
+        Res<HeroManifestHandle>,
+        Res<HeroTextureAtlasHandle>,
+    ),
     mut next_state: ResMut<NextState<GameState>>,
 ) {
     let enemy_loaded =
@@ -29,8 +34,18 @@ fn check_assets(
         asset_server.get_load_state(enemies_data_handle.0.id()) == Some(LoadState::Loaded);
     let enemies_spawn_loaded =
         asset_server.get_load_state(enemies_spawn_data_handle.0.id()) == Some(LoadState::Loaded);
+    let heroes_loaded =
+        asset_server.get_load_state(hero_manifest_handle.0.id()) == Some(LoadState::Loaded);
+    let hero_loaded =
+        asset_server.get_load_state(hero_texture_atlas_handle.0.id()) == Some(LoadState::Loaded);
 
-    if enemy_loaded && hit_loaded && enemies_loaded && enemies_spawn_loaded {
+    if enemy_loaded
+        && hit_loaded
+        && hero_loaded
+        && enemies_loaded
+        && enemies_spawn_loaded
+        && heroes_loaded
+    {
         next_state.set(GameState::MainMenu);
     }
 }
@@ -1,8 +1,11 @@
 use bevy::audio::{AudioSource, PlaybackMode, Volume};
 use bevy::prelude::*;
 
+use crate::player::LevelUpEvent;
 use crate::state::GameState;
 
+pub const BACKGROUND_MUSIC_VOLUME: f32 = 0.8;
+
 pub struct AudioPlugin;
 
 #[derive(Component)]
@@ -27,7 +30,10 @@ pub struct LevelUpSoundEffect;
 impl Plugin for AudioPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Loading), setup)
-            .add_systems(Update, (clean_up_lightning, clean_up_level_up));
+            .add_systems(
+                Update,
+                (play_level_up_sound, clean_up_lightning, clean_up_level_up),
+            );
     }
 }
 
@@ -38,7 +44,7 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
             settings: PlaybackSettings {
                 paused: true,
                 mode: PlaybackMode::Loop,
-                volume: Volume::new(0.8),
+                volume: Volume::new(BACKGROUND_MUSIC_VOLUME),
                 ..default()
             },
         },
@@ -54,6 +60,25 @@ fn setup(mut commands: Commands, asset_server: Res<AssetServer>) {
     });
 }
 
+fn play_level_up_sound(
+    mut commands: Commands,
+    level_up_sound: Res<LevelUpEffectHandle>,
+    mut level_up_events: EventReader<LevelUpEvent>,
+) {
+    for _ in level_up_events.read() {
+        commands.spawn((
+            AudioBundle {
+                source: level_up_sound.handle.clone(),
+                settings: PlaybackSettings {
+                    volume: Volume::new(0.3),
+                    ..default()
+                },
+            },
+            LevelUpSoundEffect,
+        ));
+    }
+}
+
 fn clean_up_lightning(
     mut commands: Commands,
     audio_sink: Query<(Entity, &AudioSink), With<LightningSoundEffect>>,
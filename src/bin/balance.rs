@@ -0,0 +1,266 @@
+//! Simulates seeded runs with a bot and prints per-minute difficulty curves, so the
+//! spawn timeline can be tuned without playing through it.
+//!
+//! `cargo run --bin balance -- --runs 20 --bot kiting --format json --out curves.json`
+
+use std::fs;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use eternal_gauntlet::headless::{bot_by_name, FieldStats, HeadlessRun};
+use eternal_gauntlet::spatial_index::SpatialBackend;
+use eternal_gauntlet::state::GameState;
+
+const SECONDS_PER_MINUTE: f32 = 60.0;
+
+#[derive(Debug)]
+struct Options {
+    runs: u64,
+    seed: u64,
+    minutes: u32,
+    bot: String,
+    speed: u32,
+    spatial_backend: SpatialBackend,
+    format: Format,
+    assets_dir: PathBuf,
+    out: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Csv,
+    Json,
+}
+
+/// Stats for one minute of a run, or its last partial minute if the player died.
+#[derive(Serialize, Debug, Clone, Copy, Default)]
+struct MinuteSample {
+    minute: u32,
+    enemies_alive: f32,
+    enemy_health: f32,
+    player_dps: f32,
+    experience_per_minute: f32,
+    leashed_per_minute: f32,
+}
+
+#[derive(Serialize, Debug)]
+struct RunCurve {
+    seed: u64,
+    death_time: Option<f32>,
+    minutes: Vec<MinuteSample>,
+}
+
+#[derive(Serialize, Debug)]
+struct Report {
+    bot: String,
+    runs: Vec<RunCurve>,
+    /// Per-minute average over the runs still alive at that minute.
+    average: Vec<MinuteSample>,
+    average_death_time: Option<f32>,
+}
+
+fn main() -> anyhow::Result<()> {
+    let options = Options::from_args(std::env::args().skip(1))?;
+
+    let mut runs = Vec::new();
+    for seed in options.seed..options.seed + options.runs {
+        let curve = simulate(&options, seed)?;
+        eprintln!(
+            "seed {}: {}",
+            seed,
+            match curve.death_time {
+                Some(time) => format!("died at {:.1}s", time),
+                None => format!("survived {} minutes", options.minutes),
+            }
+        );
+        runs.push(curve);
+    }
+
+    let report = Report {
+        bot: options.bot.clone(),
+        average: average_curve(&runs),
+        average_death_time: average_death_time(&runs),
+        runs,
+    };
+    let output = match options.format {
+        Format::Csv => to_csv(&report),
+        Format::Json => serde_json::to_string_pretty(&report)?,
+    };
+    match &options.out {
+        Some(path) => fs::write(path, output)?,
+        None => println!("{}", output),
+    }
+    Ok(())
+}
+
+fn simulate(options: &Options, seed: u64) -> anyhow::Result<RunCurve> {
+    let bot = bot_by_name(&options.bot)
+        .ok_or_else(|| anyhow::anyhow!("unknown bot '{}'", options.bot))?;
+    let mut run = HeadlessRun::new(&options.assets_dir, seed, bot)?;
+    run.set_speed(options.speed);
+    run.world().insert_resource(options.spatial_backend);
+
+    let mut minutes = Vec::new();
+    let mut previous = run.field_stats();
+    for minute in 1..=options.minutes {
+        let summary = run.run_for(minute as f32 * SECONDS_PER_MINUTE);
+        let stats = run.field_stats();
+        let start = (minute - 1) as f32 * SECONDS_PER_MINUTE;
+        minutes.push(sample(
+            minute,
+            &previous,
+            &stats,
+            summary.time_survived - start,
+        ));
+        previous = stats;
+
+        if run.state() != GameState::InGame {
+            return Ok(RunCurve {
+                seed,
+                death_time: Some(summary.time_survived),
+                minutes,
+            });
+        }
+    }
+
+    Ok(RunCurve {
+        seed,
+        death_time: None,
+        minutes,
+    })
+}
+
+fn sample(minute: u32, previous: &FieldStats, current: &FieldStats, elapsed: f32) -> MinuteSample {
+    let elapsed = elapsed.max(f32::EPSILON);
+    MinuteSample {
+        minute,
+        enemies_alive: current.enemies_alive as f32,
+        enemy_health: current.enemy_health,
+        player_dps: (current.damage_dealt - previous.damage_dealt) / elapsed,
+        experience_per_minute: (current.experience_earned - previous.experience_earned) / elapsed
+            * SECONDS_PER_MINUTE,
+        leashed_per_minute: (current.enemies_leashed - previous.enemies_leashed) as f32 / elapsed
+            * SECONDS_PER_MINUTE,
+    }
+}
+
+fn average_curve(runs: &[RunCurve]) -> Vec<MinuteSample> {
+    let longest = runs.iter().map(|run| run.minutes.len()).max().unwrap_or(0);
+    (0..longest)
+        .map(|index| {
+            let samples: Vec<&MinuteSample> = runs
+                .iter()
+                .filter_map(|run| run.minutes.get(index))
+                .collect();
+            let count = samples.len() as f32;
+            let mut average = MinuteSample {
+                minute: index as u32 + 1,
+                ..Default::default()
+            };
+            for sample in samples {
+                average.enemies_alive += sample.enemies_alive / count;
+                average.enemy_health += sample.enemy_health / count;
+                average.player_dps += sample.player_dps / count;
+                average.experience_per_minute += sample.experience_per_minute / count;
+                average.leashed_per_minute += sample.leashed_per_minute / count;
+            }
+            average
+        })
+        .collect()
+}
+
+fn average_death_time(runs: &[RunCurve]) -> Option<f32> {
+    let deaths: Vec<f32> = runs.iter().filter_map(|run| run.death_time).collect();
+    if deaths.is_empty() {
+        return None;
+    }
+    Some(deaths.iter().sum::<f32>() / deaths.len() as f32)
+}
+
+/// One row per run and minute, followed by the averaged curve with `seed` set to `mean`.
+fn to_csv(report: &Report) -> String {
+    let mut csv = String::from(
+        "seed,minute,enemies_alive,enemy_health,player_dps,xp_per_minute,leashed_per_minute,death_time\n",
+    );
+    let mut push_row = |seed: &str, sample: &MinuteSample, death_time: Option<f32>| {
+        csv.push_str(&format!(
+            "{},{},{:.1},{:.1},{:.2},{:.2},{:.2},{}\n",
+            seed,
+            sample.minute,
+            sample.enemies_alive,
+            sample.enemy_health,
+            sample.player_dps,
+            sample.experience_per_minute,
+            sample.leashed_per_minute,
+            death_time.map_or(String::new(), |time| format!("{:.2}", time)),
+        ));
+    };
+
+    for run in &report.runs {
+        for sample in &run.minutes {
+            push_row(&run.seed.to_string(), sample, run.death_time);
+        }
+    }
+    for sample in &report.average {
+        push_row("mean", sample, report.average_death_time);
+    }
+    csv
+}
+
+impl Options {
+    fn from_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut options = Self {
+            runs: 10,
+            seed: 0,
+            minutes: 15,
+            bot: "kiting".to_string(),
+            speed: 10,
+            spatial_backend: SpatialBackend::default(),
+            format: Format::Csv,
+            assets_dir: PathBuf::from("assets"),
+            out: None,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("{} expects a value", arg))
+            };
+            match arg.as_str() {
+                "--runs" => options.runs = value()?.parse()?,
+                "--seed" => options.seed = value()?.parse()?,
+                "--minutes" => options.minutes = value()?.parse()?,
+                "--bot" => options.bot = value()?,
+                "--speed" => {
+                    options.speed = value()?.parse()?;
+                    if options.speed == 0 {
+                        anyhow::bail!("--speed expects at least 1 tick per update");
+                    }
+                }
+                "--spatial-index" => {
+                    let name = value()?;
+                    options.spatial_backend =
+                        SpatialBackend::from_name(&name).ok_or_else(|| {
+                            anyhow::anyhow!(
+                                "unknown spatial index '{}', expected kd-tree or hash",
+                                name
+                            )
+                        })?;
+                }
+                "--format" => {
+                    options.format = match value()?.as_str() {
+                        "csv" => Format::Csv,
+                        "json" => Format::Json,
+                        other => anyhow::bail!("unknown format '{}', expected csv or json", other),
+                    }
+                }
+                "--assets" => options.assets_dir = value()?.into(),
+                "--out" => options.out = Some(value()?.into()),
+                _ => anyhow::bail!("unknown argument '{}'", arg),
+            }
+        }
+        Ok(options)
+    }
+}
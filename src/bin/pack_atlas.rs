@@ -0,0 +1,79 @@
+//! Packs sprite folders into a texture atlas image and a layout JSON with frame names.
+//!
+//! `cargo run --bin pack_atlas -- --input assets/enemies --image assets/enemy_textures.png --layout assets/enemy_texture_atlas_layout.json`
+
+use std::fs;
+use std::path::PathBuf;
+
+use image::ImageFormat;
+
+use eternal_gauntlet::generate_texture_atlas::pack_folders;
+
+#[derive(Debug)]
+struct Options {
+    inputs: Vec<PathBuf>,
+    image_path: PathBuf,
+    layout_path: PathBuf,
+    padding: u32,
+    max_size: u32,
+}
+
+fn main() -> anyhow::Result<()> {
+    let options = Options::from_args(std::env::args().skip(1))?;
+
+    let atlas = pack_folders(&options.inputs, options.padding, options.max_size)?;
+    atlas
+        .image
+        .save_with_format(&options.image_path, ImageFormat::Png)?;
+    fs::write(
+        &options.layout_path,
+        serde_json::to_string_pretty(&atlas.layout)?,
+    )?;
+
+    println!(
+        "Packed {} frames into {} ({}x{}) and {}",
+        atlas.layout.names.len(),
+        options.image_path.display(),
+        atlas.layout.size.x,
+        atlas.layout.size.y,
+        options.layout_path.display()
+    );
+    Ok(())
+}
+
+impl Options {
+    fn from_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut inputs = Vec::new();
+        let mut image_path = None;
+        let mut layout_path = None;
+        let mut padding = 0;
+        let mut max_size = 2048;
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("{} expects a value", arg))
+            };
+            match arg.as_str() {
+                "--input" => inputs.push(value()?.into()),
+                "--image" => image_path = Some(value()?.into()),
+                "--layout" => layout_path = Some(value()?.into()),
+                "--padding" => padding = value()?.parse()?,
+                "--max-size" => max_size = value()?.parse()?,
+                _ => anyhow::bail!("unknown argument '{}'", arg),
+            }
+        }
+
+        if inputs.is_empty() {
+            anyhow::bail!("at least one --input folder is required");
+        }
+        Ok(Self {
+            inputs,
+            image_path: image_path.ok_or_else(|| anyhow::anyhow!("--image is required"))?,
+            layout_path: layout_path.ok_or_else(|| anyhow::anyhow!("--layout is required"))?,
+            padding,
+            max_size,
+        })
+    }
+}
@@ -0,0 +1,238 @@
+//! Prints the planned spawn rate per race and power over several loops of
+//! `enemy_spawns.json`, and flags gaps, overlaps and the enemy cap.
+//!
+//! `cargo run --bin spawn_timeline -- --loops 3 --step 15`
+
+use std::fs;
+use std::path::PathBuf;
+
+use eternal_gauntlet::enemy::{EnemiesData, SpawnData};
+use eternal_gauntlet::spawn_timeline::{loop_health_multiplier, SpawnTimeline};
+use eternal_gauntlet::utils::format_time;
+use eternal_gauntlet::MAX_NUM_ENEMIES;
+
+#[derive(Debug)]
+struct Options {
+    spawns_path: PathBuf,
+    enemies_path: PathBuf,
+    loops: u32,
+    step: f32,
+    csv: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+    let options = Options::from_args(std::env::args().skip(1))?;
+    let spawn_data: SpawnData = serde_json::from_str(&fs::read_to_string(&options.spawns_path)?)?;
+    let enemies_data: EnemiesData =
+        serde_json::from_str(&fs::read_to_string(&options.enemies_path)?)?;
+    if let Err(errors) = enemies_data.validate(&spawn_data) {
+        for e in errors.iter() {
+            eprintln!("INVALID  {}", e);
+        }
+        anyhow::bail!(
+            "{} does not match {}",
+            options.spawns_path.display(),
+            options.enemies_path.display()
+        );
+    }
+
+    let timeline = SpawnTimeline::new(&spawn_data);
+    if timeline.loop_length() <= 0.0 {
+        anyhow::bail!("{} has no spawns", options.spawns_path.display());
+    }
+
+    let columns = columns(&timeline);
+    let end = timeline.loop_length() * options.loops as f32;
+    let times = (0..)
+        .map(|step| step as f32 * options.step)
+        .take_while(|time| *time < end);
+
+    if options.csv {
+        print_csv(&timeline, &columns, times);
+    } else {
+        print_table(&timeline, &columns, times, options.loops);
+        print_warnings(&timeline, end);
+    }
+    Ok(())
+}
+
+/// One column per race and power, in the order they first appear.
+fn columns(timeline: &SpawnTimeline) -> Vec<(String, String)> {
+    let mut columns: Vec<(String, String)> = Vec::new();
+    for spawn in timeline.spawns() {
+        let column = (spawn.race.clone(), spawn.power.clone());
+        if !columns.contains(&column) {
+            columns.push(column);
+        }
+    }
+    columns
+}
+
+/// Enemies per second spawned for one race and power at `time`.
+fn column_rate(timeline: &SpawnTimeline, (race, power): &(String, String), time: f32) -> f32 {
+    timeline
+        .spawns()
+        .iter()
+        .filter(|spawn| &spawn.race == race && &spawn.power == power)
+        .filter(|spawn| timeline.is_active(spawn, time))
+        .map(SpawnTimeline::rate)
+        .sum()
+}
+
+fn print_csv(
+    timeline: &SpawnTimeline,
+    columns: &[(String, String)],
+    times: impl Iterator<Item = f32>,
+) {
+    let mut header = String::from("time,loop,health_multiplier");
+    for (race, power) in columns {
+        header.push_str(&format!(",{} {}", race, power));
+    }
+    println!("{},total_rate,on_field_without_kills", header);
+
+    for time in times {
+        let loop_index = timeline.loop_index(time);
+        let mut row = format!(
+            "{},{},{}",
+            time,
+            loop_index + 1,
+            loop_health_multiplier(loop_index)
+        );
+        for column in columns {
+            row.push_str(&format!(",{:.2}", column_rate(timeline, column, time)));
+        }
+        println!(
+            "{},{:.2},{}",
+            row,
+            timeline.total_rate(time),
+            timeline.planned_total(time)
+        );
+    }
+}
+
+fn print_table(
+    timeline: &SpawnTimeline,
+    columns: &[(String, String)],
+    times: impl Iterator<Item = f32>,
+    loops: u32,
+) {
+    println!(
+        "{} entries, loop length {}, showing {} loops. Rates in enemies per second.\n",
+        timeline.spawns().len(),
+        format_time(timeline.loop_length()),
+        loops
+    );
+    println!(
+        "{:>6} {:>4} {:>4}  {:<40} {:>7} {:>8}",
+        "time", "loop", "hp", "spawning", "total", "on field"
+    );
+
+    for time in times {
+        let loop_index = timeline.loop_index(time);
+        let spawning: Vec<String> = columns
+            .iter()
+            .filter_map(|column| {
+                let rate = column_rate(timeline, column, time);
+                (rate > 0.0).then(|| format!("{} {} {:.1}", column.0, column.1, rate))
+            })
+            .collect();
+        println!(
+            "{:>6} {:>4} {:>3}x  {:<40} {:>7.1} {:>8}",
+            format_time(time),
+            loop_index + 1,
+            loop_health_multiplier(loop_index),
+            spawning.join(", "),
+            timeline.total_rate(time),
+            timeline.planned_total(time)
+        );
+    }
+}
+
+fn print_warnings(timeline: &SpawnTimeline, end: f32) {
+    println!();
+    let spawns = timeline.spawns();
+
+    for gap in timeline.gaps() {
+        println!(
+            "GAP      nothing spawns from {} to {}",
+            format_time(gap.start),
+            format_time(gap.end)
+        );
+    }
+
+    for overlap in timeline.overlaps() {
+        let first = &spawns[overlap.first];
+        println!(
+            "OVERLAP  {} {} spawns from entries {} and {} between {} and {}",
+            first.race,
+            first.power,
+            overlap.first,
+            overlap.second,
+            format_time(overlap.start),
+            format_time(overlap.end)
+        );
+    }
+
+    let (peak_time, peak_rate) = timeline.peak_rate();
+    let active = spawns
+        .iter()
+        .filter(|spawn| timeline.is_active(spawn, peak_time))
+        .count();
+    println!(
+        "PEAK     {:.1} enemies/s at {} with {} entries active",
+        peak_rate,
+        format_time(peak_time),
+        active
+    );
+
+    let on_field = timeline.planned_total(end);
+    let cap = MAX_NUM_ENEMIES as u32;
+    println!(
+        "CAP      {} enemies spawned by {} without kills, MAX_NUM_ENEMIES is {}",
+        on_field,
+        format_time(end),
+        cap
+    );
+    if on_field > cap {
+        let mut time = 0.0;
+        while timeline.planned_total(time) <= cap {
+            time += 1.0;
+        }
+        println!(
+            "OVER CAP the cap is reached at {} if nothing is killed",
+            format_time(time)
+        );
+    }
+}
+
+impl Options {
+    fn from_args(args: impl IntoIterator<Item = String>) -> anyhow::Result<Self> {
+        let mut options = Self {
+            spawns_path: PathBuf::from("assets/enemy_spawns.json"),
+            enemies_path: PathBuf::from("assets/enemies.json"),
+            loops: 3,
+            step: 15.0,
+            csv: false,
+        };
+
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| anyhow::anyhow!("{} expects a value", arg))
+            };
+            match arg.as_str() {
+                "--spawns" => options.spawns_path = value()?.into(),
+                "--enemies" => options.enemies_path = value()?.into(),
+                "--loops" => options.loops = value()?.parse()?,
+                "--step" => options.step = value()?.parse()?,
+                "--csv" => options.csv = true,
+                _ => anyhow::bail!("unknown argument '{}'", arg),
+            }
+        }
+        if options.step <= 0.0 {
+            anyhow::bail!("--step must be positive");
+        }
+        Ok(options)
+    }
+}
@@ -0,0 +1,425 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use bevy::audio::Volume;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::audio::{BackgroundMusic, BACKGROUND_MUSIC_VOLUME};
+use crate::hero_registry::{build_hero_registry, HeroRegistry};
+use crate::replay::{ActiveReplay, Replay, REPLAY_FORMAT_VERSION};
+use crate::resources::{RunRng, RunStats, Score};
+use crate::state::GameState;
+use crate::world::{cosmetic_tint, SelectedCharacter, SelectedCosmetic};
+
+pub const GAME_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Final result of a run, reported to the host once the player dies.
+#[derive(Serialize, Debug, Clone)]
+pub struct RunResult {
+    pub hero: String,
+    pub score: u32,
+    pub time_survived: f32,
+    pub level: u32,
+    pub kills: u32,
+    pub kills_by_race: BTreeMap<String, u32>,
+    pub seed: Option<u64>,
+    pub version: String,
+}
+
+/// Messages sent from the game to the host page (or native sink).
+#[derive(Serialize, Debug, Clone)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostMessage {
+    RunResult(RunResult),
+    Replay(Replay),
+    ReplayCheck {
+        expected_score: Option<u32>,
+        score: u32,
+        matches: bool,
+    },
+    CommandError {
+        message: String,
+        payload: String,
+    },
+}
+
+/// Commands sent from the host page to the game.
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HostCommand {
+    SelectCharacter { hero: String },
+    StartRun,
+    Pause,
+    Resume,
+    SetVolume { volume: f32 },
+    SetSeed { seed: u64 },
+    ApplyCosmetic { cosmetic: String },
+    PlayReplay { replay: Replay },
+}
+
+/// A parsed host command along with the raw payload it came from, used for error replies.
+#[derive(Event, Debug, Clone)]
+pub struct HostCommandEvent {
+    pub command: HostCommand,
+    pub payload: String,
+}
+
+/// Seed requested by the host or command line. Used for every following run until changed.
+#[derive(Resource, Default, Debug)]
+pub struct RequestedSeed(pub Option<u64>);
+
+/// Destination for serialized host messages.
+pub trait HostSink: Send + Sync {
+    fn send(&self, payload: &str) -> anyhow::Result<()>;
+}
+
+/// Appends each message as a JSON line to a file.
+pub struct FileSink {
+    path: PathBuf,
+}
+
+/// Prints each message as a JSON line to stdout.
+pub struct StdoutSink;
+
+#[derive(Resource)]
+pub struct HostBridge(Box<dyn HostSink>);
+
+/// A hero selected by the host while assets were loading, checked once the
+/// hero registry is built.
+#[derive(Resource, Default, Debug)]
+pub struct PendingHeroSelection(Option<HostCommandEvent>);
+
+static HOST_COMMAND_QUEUE: Mutex<VecDeque<String>> = Mutex::new(VecDeque::new());
+
+pub struct HostBridgePlugin;
+
+impl Plugin for HostBridgePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<HostCommandEvent>()
+            .init_resource::<RequestedSeed>()
+            .init_resource::<SelectedCosmetic>()
+            .init_resource::<PendingHeroSelection>()
+            .add_systems(OnEnter(GameState::GameOver), send_run_result)
+            .add_systems(
+                OnExit(GameState::Loading),
+                apply_pending_hero_selection.after(build_hero_registry),
+            )
+            .add_systems(
+                Update,
+                (
+                    read_host_commands,
+                    (
+                        handle_select_character,
+                        handle_run_commands,
+                        handle_set_volume,
+                        handle_set_seed,
+                        handle_apply_cosmetic,
+                    ),
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Queues a raw message from the host. Messages are processed in order on the next frame.
+pub fn queue_host_command(message: String) {
+    HOST_COMMAND_QUEUE.lock().unwrap().push_back(message);
+}
+
+/// Parses a host message. Plain strings are treated as a hero id, which is what
+/// older frontends send.
+pub fn parse_host_command(message: &str) -> anyhow::Result<HostCommand> {
+    let message = message.trim();
+    if !message.starts_with('{') {
+        return Ok(HostCommand::SelectCharacter {
+            hero: message.to_string(),
+        });
+    }
+
+    Ok(serde_json::from_str(message)?)
+}
+
+fn read_host_commands(mut ew: EventWriter<HostCommandEvent>, bridge: Option<Res<HostBridge>>) {
+    let messages: Vec<String> = HOST_COMMAND_QUEUE.lock().unwrap().drain(..).collect();
+    for payload in messages {
+        info!("Host command: {}", payload);
+        match parse_host_command(&payload) {
+            Ok(command) => {
+                ew.send(HostCommandEvent { command, payload });
+            }
+            Err(e) => reply_error(
+                bridge.as_deref(),
+                &payload,
+                format!("Invalid command: {}", e),
+            ),
+        }
+    }
+}
+
+fn handle_select_character(
+    mut events: EventReader<HostCommandEvent>,
+    mut selected_character: ResMut<SelectedCharacter>,
+    mut pending_selection: ResMut<PendingHeroSelection>,
+    hero_registry: Res<HeroRegistry>,
+    state: Res<State<GameState>>,
+    bridge: Option<Res<HostBridge>>,
+) {
+    for event in events.read() {
+        if !matches!(event.command, HostCommand::SelectCharacter { .. }) {
+            continue;
+        }
+
+        if *state.get() == GameState::Loading {
+            pending_selection.0 = Some(event.clone());
+        } else {
+            select_character(
+                event,
+                &mut selected_character,
+                &hero_registry,
+                bridge.as_deref(),
+            );
+        }
+    }
+}
+
+pub fn apply_pending_hero_selection(
+    mut selected_character: ResMut<SelectedCharacter>,
+    mut pending_selection: ResMut<PendingHeroSelection>,
+    hero_registry: Res<HeroRegistry>,
+    bridge: Option<Res<HostBridge>>,
+) {
+    if let Some(event) = pending_selection.0.take() {
+        select_character(
+            &event,
+            &mut selected_character,
+            &hero_registry,
+            bridge.as_deref(),
+        );
+    }
+}
+
+fn select_character(
+    event: &HostCommandEvent,
+    selected_character: &mut SelectedCharacter,
+    hero_registry: &HeroRegistry,
+    bridge: Option<&HostBridge>,
+) {
+    let HostCommand::SelectCharacter { hero } = &event.command else {
+        return;
+    };
+
+    match hero_registry.get(hero) {
+        Ok(_) => selected_character.0 = Some(hero.clone()),
+        Err(e) => reply_error(bridge, &event.payload, e.to_string()),
+    }
+}
+
+fn handle_run_commands(
+    mut commands: Commands,
+    mut events: EventReader<HostCommandEvent>,
+    state: Res<State<GameState>>,
+    mut next_state: ResMut<NextState<GameState>>,
+    mut time: ResMut<Time<Virtual>>,
+    hero_registry: Res<HeroRegistry>,
+    bridge: Option<Res<HostBridge>>,
+) {
+    for event in events.read() {
+        let valid = match &event.command {
+            HostCommand::StartRun => {
+                let valid = matches!(state.get(), GameState::MainMenu | GameState::GameOver);
+                if valid {
+                    next_state.set(GameState::GameInit);
+                }
+                valid
+            }
+            HostCommand::PlayReplay { replay } => {
+                if replay.format != REPLAY_FORMAT_VERSION {
+                    reply_error(
+                        bridge.as_deref(),
+                        &event.payload,
+                        format!("Unsupported replay format {}", replay.format),
+                    );
+                    continue;
+                }
+                if let Err(e) = hero_registry.get(&replay.hero) {
+                    reply_error(bridge.as_deref(), &event.payload, e.to_string());
+                    continue;
+                }
+                let valid = matches!(state.get(), GameState::MainMenu | GameState::GameOver);
+                if valid {
+                    commands.insert_resource(ActiveReplay::new(replay.clone()));
+                    next_state.set(GameState::GameInit);
+                }
+                valid
+            }
+            HostCommand::Pause => {
+                let valid = *state.get() == GameState::InGame && !time.is_paused();
+                if valid {
+                    time.pause();
+                }
+                valid
+            }
+            HostCommand::Resume => {
+                let valid = time.is_paused();
+                if valid {
+                    time.unpause();
+                }
+                valid
+            }
+            _ => continue,
+        };
+
+        if !valid {
+            reply_error(
+                bridge.as_deref(),
+                &event.payload,
+                format!("Command not allowed in state {:?}", state.get()),
+            );
+        }
+    }
+}
+
+fn handle_set_volume(
+    mut events: EventReader<HostCommandEvent>,
+    mut global_volume: ResMut<GlobalVolume>,
+    background_music: Query<&AudioSink, With<BackgroundMusic>>,
+    bridge: Option<Res<HostBridge>>,
+) {
+    for event in events.read() {
+        let HostCommand::SetVolume { volume } = event.command else {
+            continue;
+        };
+
+        if !(0.0..=1.0).contains(&volume) {
+            reply_error(
+                bridge.as_deref(),
+                &event.payload,
+                format!("Volume must be between 0 and 1, got {}", volume),
+            );
+            continue;
+        }
+
+        global_volume.volume = Volume::new(volume);
+        if let Ok(sink) = background_music.get_single() {
+            sink.set_volume(BACKGROUND_MUSIC_VOLUME * volume);
+        }
+    }
+}
+
+fn handle_set_seed(
+    mut events: EventReader<HostCommandEvent>,
+    mut requested_seed: ResMut<RequestedSeed>,
+) {
+    for event in events.read() {
+        if let HostCommand::SetSeed { seed } = event.command {
+            requested_seed.0 = Some(seed);
+        }
+    }
+}
+
+fn handle_apply_cosmetic(
+    mut events: EventReader<HostCommandEvent>,
+    mut selected_cosmetic: ResMut<SelectedCosmetic>,
+    bridge: Option<Res<HostBridge>>,
+) {
+    for event in events.read() {
+        let HostCommand::ApplyCosmetic { cosmetic } = &event.command else {
+            continue;
+        };
+
+        if cosmetic_tint(cosmetic).is_some() {
+            selected_cosmetic.0 = Some(cosmetic.clone());
+        } else {
+            reply_error(
+                bridge.as_deref(),
+                &event.payload,
+                format!("Unknown cosmetic '{}'", cosmetic),
+            );
+        }
+    }
+}
+
+fn reply_error(bridge: Option<&HostBridge>, payload: &str, message: String) {
+    warn!("{}", message);
+    if let Some(bridge) = bridge {
+        bridge.send(&HostMessage::CommandError {
+            message,
+            payload: payload.to_string(),
+        });
+    }
+}
+
+fn send_run_result(
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    selected_character: Res<SelectedCharacter>,
+    run_rng: Res<RunRng>,
+    active_replay: Option<Res<ActiveReplay>>,
+    bridge: Option<Res<HostBridge>>,
+) {
+    info!("Score: {}", score.0);
+    let Some(bridge) = bridge else {
+        return;
+    };
+    if active_replay.is_some() {
+        return;
+    }
+    if score.0 == 0 {
+        return;
+    }
+
+    let result = RunResult {
+        hero: selected_character.0.clone().unwrap_or_default(),
+        score: score.0,
+        time_survived: run_stats.time_survived,
+        level: run_stats.level_reached,
+        kills: run_stats.total_kills(),
+        kills_by_race: run_stats.kills.clone(),
+        seed: Some(run_rng.seed()),
+        version: GAME_VERSION.to_string(),
+    };
+    bridge.send(&HostMessage::RunResult(result));
+}
+
+impl HostBridge {
+    pub fn new(sink: impl HostSink + 'static) -> Self {
+        Self(Box::new(sink))
+    }
+
+    pub fn send(&self, message: &HostMessage) {
+        let result = serde_json::to_string(message)
+            .map_err(anyhow::Error::from)
+            .and_then(|payload| self.0.send(&payload));
+        if let Err(e) = result {
+            warn!("Failed to send message to host: {}", e);
+        }
+    }
+}
+
+impl FileSink {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl HostSink for FileSink {
+    fn send(&self, payload: &str) -> anyhow::Result<()> {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}", payload)?;
+        Ok(())
+    }
+}
+
+impl HostSink for StdoutSink {
+    fn send(&self, payload: &str) -> anyhow::Result<()> {
+        println!("{}", payload);
+        Ok(())
+    }
+}
@@ -1,8 +1,11 @@
 use bevy::render::camera::ScalingMode;
+use bevy::transform::TransformSystem;
 use bevy::{math::vec3, prelude::*};
 
 use crate::player::Player;
+use crate::simulation::InterpolationSet;
 use crate::state::GameState;
+use crate::CAMERA_FOLLOW_RATE;
 
 pub struct FollowCameraPlugin;
 
@@ -10,8 +13,11 @@ impl Plugin for FollowCameraPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(OnEnter(GameState::Loading), setup_camera)
             .add_systems(
-                Update,
-                camera_follow_player.run_if(in_state(GameState::InGame)),
+                PostUpdate,
+                camera_follow_player
+                    .after(InterpolationSet)
+                    .before(TransformSystem::TransformPropagate)
+                    .run_if(in_state(GameState::InGame)),
             );
     }
 }
@@ -26,6 +32,7 @@ fn setup_camera(mut commands: Commands) {
 fn camera_follow_player(
     player_query: Query<&Transform, With<Player>>,
     mut camera_query: Query<&mut Transform, (With<Camera>, Without<Player>)>,
+    time: Res<Time>,
 ) {
     if camera_query.is_empty() || player_query.is_empty() {
         return;
@@ -35,5 +42,6 @@ fn camera_follow_player(
     let player_transform = player_query.single().translation;
     let (x, y) = (player_transform.x, player_transform.y);
 
-    camera_transform.translation = camera_transform.translation.lerp(vec3(x, y, 0.0), 0.1);
+    let t = 1.0 - (-CAMERA_FOLLOW_RATE * time.delta_seconds()).exp();
+    camera_transform.translation = camera_transform.translation.lerp(vec3(x, y, 0.0), t);
 }
@@ -1,81 +1,243 @@
-use bevy::utils::Duration;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::Deserialize;
 
-use bevy::{prelude::*, time::common_conditions::on_timer};
-use kd_tree::{KdPoint, KdTree};
-
-use crate::player::{Player, PlayerEnemyCollisionEvent};
+use crate::enemy::Enemy;
+use crate::simulation::SimSet;
+use crate::spatial_index::SpatialIndex;
 use crate::*;
-use crate::{enemy::Enemy, state::GameState};
 
 pub struct CollisionPlugin;
 
-#[derive(Component)]
-struct Collidable {
-    pos: Vec2,
-    entity: Entity,
+/// Shape of a hitbox in sprite pixels.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum HitboxShape {
+    /// Radius.
+    Circle(f32),
+    /// Width and height.
+    Rect(Vec2),
+}
+
+/// Area an entity collides with, in sprite pixels around the sprite center.
+/// Scales with the entity's transform, like its sprite.
+#[derive(Component, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct Hitbox {
+    pub shape: HitboxShape,
+    #[serde(default)]
+    pub offset: Vec2,
+}
+
+/// A hitbox placed in the world.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Collider {
+    Circle { center: Vec2, radius: f32 },
+    Rect(Rect),
+}
+
+/// Which contacts an entity takes part in, see [`CollisionLayer::interacts_with`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CollisionLayer {
+    Player,
+    Enemy,
+    PlayerProjectile,
+    EnemyProjectile,
+    Pickup,
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct PlayerEnemyCollisionEvent {
+    pub player: Entity,
+    pub enemy: Entity,
+}
+
+/// A player projectile touching an enemy, or an enemy projectile touching the player.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct ProjectileHitEvent {
+    pub projectile: Entity,
+    pub target: Entity,
+}
+
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct PickupContactEvent {
+    pub pickup: Entity,
+    pub player: Entity,
+}
+
+#[derive(SystemParam)]
+struct ContactWriters<'w> {
+    player_enemy: EventWriter<'w, PlayerEnemyCollisionEvent>,
+    projectile_hit: EventWriter<'w, ProjectileHitEvent>,
+    pickup: EventWriter<'w, PickupContactEvent>,
 }
-#[derive(Resource)]
-struct EnemyKdTree(KdTree<Collidable>);
 
 impl Plugin for CollisionPlugin {
     fn build(&self, app: &mut App) {
-        app.insert_resource(EnemyKdTree::default()).add_systems(
-            Update,
-            (
-                handle_enemy_player_collision,
-                update_enemy_kd_tree
-                    .run_if(on_timer(Duration::from_secs_f32(KD_TREE_REFRESH_RATE))),
-            )
-                .run_if(in_state(GameState::InGame)),
-        );
+        app.add_event::<PlayerEnemyCollisionEvent>()
+            .add_event::<ProjectileHitEvent>()
+            .add_event::<PickupContactEvent>()
+            .add_systems(FixedUpdate, detect_contacts.in_set(SimSet::Combat));
     }
 }
 
-fn handle_enemy_player_collision(
-    player_query: Query<&Transform, With<Player>>,
-    tree: Res<EnemyKdTree>,
-    mut ew: EventWriter<PlayerEnemyCollisionEvent>,
-) {
-    if player_query.is_empty() {
-        return;
+impl Hitbox {
+    pub fn circle(radius: f32) -> Self {
+        Self {
+            shape: HitboxShape::Circle(radius),
+            offset: Vec2::ZERO,
+        }
     }
 
-    let player_pos = player_query.single().translation;
-    let enemies = tree.0.within_radius(&[player_pos.x, player_pos.y], 50.0);
-    for _ in enemies.iter() {
-        ew.send(PlayerEnemyCollisionEvent);
+    pub fn rect(size: Vec2) -> Self {
+        Self {
+            shape: HitboxShape::Rect(size),
+            offset: Vec2::ZERO,
+        }
     }
-}
 
-fn update_enemy_kd_tree(
-    mut tree: ResMut<EnemyKdTree>,
-    enemy_query: Query<(&Transform, Entity), With<Enemy>>,
-) {
-    let mut items = Vec::new();
-    for (t, e) in enemy_query.iter() {
-        items.push(Collidable {
-            entity: e,
-            pos: t.translation.truncate(),
-        })
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// Farthest the hitbox reaches from the sprite center, in sprite pixels.
+    pub fn reach(&self) -> f32 {
+        let extent = match self.shape {
+            HitboxShape::Circle(radius) => radius,
+            HitboxShape::Rect(size) => size.length() / 2.0,
+        };
+        self.offset.length() + extent
+    }
+
+    /// Smallest and largest corner of the hitbox, in sprite pixels.
+    pub fn bounds(&self) -> Rect {
+        let half_size = match self.shape {
+            HitboxShape::Circle(radius) => Vec2::splat(radius),
+            HitboxShape::Rect(size) => size / 2.0,
+        };
+        Rect::from_center_half_size(self.offset, half_size)
     }
 
-    tree.0 = KdTree::build_by_ordered_float(items);
+    pub fn place(&self, transform: &Transform) -> Collider {
+        let scale = transform.scale.truncate();
+        let center = transform.translation.truncate() + self.offset * scale;
+        match self.shape {
+            HitboxShape::Circle(radius) => Collider::Circle {
+                center,
+                radius: radius * scale.max_element(),
+            },
+            HitboxShape::Rect(size) => Collider::Rect(Rect::from_center_size(center, size * scale)),
+        }
+    }
 }
 
-impl KdPoint for Collidable {
-    type Scalar = f32;
-    type Dim = typenum::U2;
-    fn at(&self, k: usize) -> f32 {
-        if k == 0 {
-            return self.pos.x;
+impl Collider {
+    pub fn intersects(&self, other: &Collider) -> bool {
+        match (*self, *other) {
+            (
+                Collider::Circle { center, radius },
+                Collider::Circle {
+                    center: other_center,
+                    radius: other_radius,
+                },
+            ) => center.distance_squared(other_center) <= (radius + other_radius).powi(2),
+            // Touching edges count, as they do for circles
+            (Collider::Rect(rect), Collider::Rect(other_rect)) => {
+                rect.min.cmple(other_rect.max).all() && other_rect.min.cmple(rect.max).all()
+            }
+            (Collider::Circle { center, radius }, Collider::Rect(rect))
+            | (Collider::Rect(rect), Collider::Circle { center, radius }) => {
+                center.distance_squared(center.clamp(rect.min, rect.max)) <= radius * radius
+            }
         }
+    }
+}
 
-        self.pos.y
+impl CollisionLayer {
+    /// Enemies don't collide with each other, and projectiles don't hit their own side.
+    pub fn interacts_with(self, other: Self) -> bool {
+        use CollisionLayer::*;
+        matches!(
+            (self, other),
+            (Player, Enemy)
+                | (Enemy, Player)
+                | (Player, EnemyProjectile)
+                | (EnemyProjectile, Player)
+                | (Player, Pickup)
+                | (Pickup, Player)
+                | (Enemy, PlayerProjectile)
+                | (PlayerProjectile, Enemy)
+        )
     }
 }
 
-impl Default for EnemyKdTree {
-    fn default() -> Self {
-        Self(KdTree::build_by_ordered_float(vec![]))
+impl ContactWriters<'_> {
+    fn send(&mut self, a: (Entity, CollisionLayer), b: (Entity, CollisionLayer)) {
+        use CollisionLayer::*;
+        match (a.1, b.1) {
+            (Player, Enemy) => {
+                self.player_enemy.send(PlayerEnemyCollisionEvent {
+                    player: a.0,
+                    enemy: b.0,
+                });
+            }
+            (Player, EnemyProjectile) | (Enemy, PlayerProjectile) => {
+                self.projectile_hit.send(ProjectileHitEvent {
+                    projectile: b.0,
+                    target: a.0,
+                });
+            }
+            (Player, Pickup) => {
+                self.pickup.send(PickupContactEvent {
+                    pickup: b.0,
+                    player: a.0,
+                });
+            }
+            (Enemy, Player)
+            | (EnemyProjectile, Player)
+            | (PlayerProjectile, Enemy)
+            | (Pickup, Player) => self.send(b, a),
+            _ => {}
+        }
+    }
+}
+
+/// Sends a contact event for every overlapping pair of hitboxes whose layers
+/// interact. Enemies are looked up through the spatial index, everything else
+/// is checked pairwise.
+fn detect_contacts(
+    collider_query: Query<(Entity, &Transform, &Hitbox, &CollisionLayer), Without<Enemy>>,
+    enemy_query: Query<(&Transform, &Hitbox, &CollisionLayer), With<Enemy>>,
+    spatial_index: SpatialIndex,
+    mut contacts: ContactWriters,
+) {
+    let colliders: Vec<_> = collider_query
+        .iter()
+        .map(|(entity, transform, hitbox, layer)| {
+            let reach = hitbox.reach() * transform.scale.truncate().max_element();
+            let position = transform.translation.truncate();
+            (entity, *layer, hitbox.place(transform), position, reach)
+        })
+        .collect();
+
+    for (index, (entity, layer, collider, position, reach)) in colliders.iter().enumerate() {
+        if layer.interacts_with(CollisionLayer::Enemy) {
+            for hit in spatial_index.within_radius(*position, reach + MAX_ENEMY_HITBOX_REACH) {
+                let Ok((transform, hitbox, enemy_layer)) = enemy_query.get(hit.entity) else {
+                    continue;
+                };
+                if layer.interacts_with(*enemy_layer)
+                    && collider.intersects(&hitbox.place(transform))
+                {
+                    contacts.send((*entity, *layer), (hit.entity, *enemy_layer));
+                }
+            }
+        }
+
+        for (other, other_layer, other_collider, ..) in colliders.iter().skip(index + 1) {
+            if layer.interacts_with(*other_layer) && collider.intersects(other_collider) {
+                contacts.send((*entity, *layer), (*other, *other_layer));
+            }
+        }
     }
 }
@@ -16,19 +16,33 @@ pub const WORLD_W: f32 = 3000.0;
 pub const WORLD_H: f32 = 2500.0;
 
 // Player
-pub const PLAYER_SPEED: f32 = 2.0;
+pub const PLAYER_SPEED: f32 = 120.0; // units per second
 pub const PLAYER_HEALTH: f32 = 100.0;
+pub const PLAYER_HURT_DURATION: f32 = 0.3; // seconds in the hurt state after an enemy touches the player
+pub const PLAYER_DEATH_DURATION: f32 = 1.0; // seconds the death clip plays before the game over screen
 
 // Enemy
 pub const MAX_NUM_ENEMIES: usize = 20000;
-pub const ENEMY_DAMAGE: f32 = 1.0;
+pub const ENEMY_DAMAGE: f32 = 60.0; // per second of contact, per enemy
 pub const SPAWN_RATE_PER_SECOND: usize = 30;
 pub const ENEMY_HEALTH: f32 = 100.0;
 pub const ENEMY_SPAWN_INTERVAL: f32 = 1.0;
-pub const ENEMY_SPEED: f32 = 1.0;
+pub const ENEMY_SPEED: f32 = 60.0; // units per second
+pub const ENEMY_SPAWN_RING_MIN: f32 = 1000.0; // distance from the player
+pub const ENEMY_SPAWN_RING_MAX: f32 = 5000.0;
+pub const ENEMY_LEASH_DISTANCE: f32 = 6000.0; // beyond this enemies move back to the spawn ring
 
-// Kd-tree
+// Collision
+pub const PLAYER_HITBOX_RADIUS: f32 = 8.0; // sprite pixels
+pub const PLAYER_HITBOX_OFFSET_Y: f32 = -4.0; // sprite pixels, below the sprite center
+pub const MAX_ENEMY_HITBOX_REACH: f32 = 70.0; // world units from the enemy's position
+
+// Camera
+pub const CAMERA_FOLLOW_RATE: f32 = 6.3; // higher catches up faster, frame rate independent
+
+// Spatial index
 pub const KD_TREE_REFRESH_RATE: f32 = 0.1;
+pub const SPATIAL_HASH_CELL_SIZE: f32 = 150.0;
 
 // Gun
 pub const BULLET_SPAWN_INTERVAL: f32 = 0.1;
@@ -1,25 +1,29 @@
 use std::f32::consts::PI;
 
+use bevy::ecs::query::QueryData;
 use bevy::math::vec3;
 use bevy::prelude::*;
 use rand::Rng;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use std::time::Duration;
 use uuid::Uuid;
 
+use crate::animation::ClipDefinition;
+use crate::collision::Hitbox;
 use crate::enemy_bundle::EnemyBundle;
-use crate::enemy_textures::EnemyTextureAtlas;
-use crate::player::Experience;
 use crate::player::Player;
+use crate::player::{Experience, ExperienceMultiplier};
+use crate::simulation::{InterpolatedTransform, SimSet};
+use crate::spawn_timeline::{calculate_spawn_count, loop_health_multiplier, SpawnTimeline};
 use crate::state::GameState;
 use crate::*;
 
-use self::animation::AnimationIndices;
-use self::resources::Score;
+use self::resources::{RunRng, RunStats, Score};
 
 #[derive(Resource, Debug)]
-struct GameTime(f32);
+pub struct GameTime(pub f32);
 
 #[derive(Resource)]
 pub struct EnemiesDataHandle(pub Handle<EnemiesData>);
@@ -31,17 +35,50 @@ pub struct EnemyStats {
     pub power: u32,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
-pub struct EnemyLevels {
+/// Every enemy race keyed by id, as defined in `enemies.json`. Spawn entries
+/// refer to races by id and to tiers by their key within the race.
+#[derive(Deserialize, Asset, TypePath, Clone)]
+pub struct EnemiesData {
+    pub races: BTreeMap<String, EnemyRace>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EnemyRace {
+    pub display_name: String,
+    pub tiers: BTreeMap<String, EnemyTier>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct EnemyTier {
     #[serde(flatten)]
-    pub levels: HashMap<String, EnemyStats>,
+    pub stats: EnemyStats,
+    /// Clip of the enemy texture atlas the tier walks with.
+    pub clip: ClipDefinition,
+    /// Sized to the clip's frames, in sprite pixels.
+    pub hitbox: Hitbox,
 }
 
-#[derive(Deserialize, Asset, TypePath, Clone)]
-pub struct EnemiesData {
-    pub undead: EnemyLevels,
-    pub orc: EnemyLevels,
-    pub demon: EnemyLevels,
+/// A spawn entry that refers to a race or tier missing from `enemies.json`,
+/// or to a tier whose hitbox collision detection can't find.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SpawnDataError {
+    UnknownRace {
+        entry: usize,
+        race: String,
+    },
+    UnknownTier {
+        entry: usize,
+        race: String,
+        tier: String,
+    },
+    /// The hitbox reaches past `MAX_ENEMY_HITBOX_REACH`, so contacts at its
+    /// edge would go undetected.
+    HitboxTooLarge {
+        entry: usize,
+        race: String,
+        tier: String,
+        reach: f32,
+    },
 }
 
 #[derive(Resource)]
@@ -60,12 +97,12 @@ pub struct SpawnDataResource(pub SpawnData);
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct EnemySpawn {
-    id: String,
-    race: String,
-    power: String,
-    count: u32,
-    start_time: String,
-    end_time: String,
+    pub id: String,
+    pub race: String,
+    pub power: String,
+    pub count: u32,
+    pub start_time: String,
+    pub end_time: String,
 }
 
 #[derive(Resource, Debug)]
@@ -74,8 +111,49 @@ struct SpawnTimer(Timer);
 #[derive(Resource)]
 struct SpawnTracker(HashMap<String, u32>);
 
-#[derive(Resource)]
-struct EnemyMaxSpawnTime(f32);
+/// Zero-based index of the spawn timeline loop currently playing, from
+/// [`SpawnTimeline::loop_index`].
+#[derive(Resource, Debug, Default)]
+pub struct SpawnLoop(pub u32);
+
+/// An enemy was moved to a new position instead of walking there, by
+/// `leash_enemies` or when it was recycled into a new spawn.
+#[derive(Event, Debug, Clone, Copy, PartialEq)]
+pub struct EnemyTeleportEvent(pub Entity);
+
+#[derive(Event)]
+pub struct NewLoopEvent {
+    pub loop_index: u32,
+    pub health_multiplier: u32,
+}
+
+/// What a scheduled spawn does once `SpawnBudget::max_enemies` are alive.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RecycleStrategy {
+    /// Turns the enemy farthest from the player into the new spawn.
+    #[default]
+    Farthest,
+    /// Turns the enemy with the lowest power, then health, into the new spawn.
+    Weakest,
+    /// Skips the spawn.
+    Drop,
+}
+
+/// Enemies farther than `distance` from the player are moved back to the
+/// spawn ring, keeping their stats and health. Always beyond
+/// `ENEMY_SPAWN_RING_MAX`, so new spawns and leashed enemies aren't leashed
+/// again right away.
+#[derive(Resource, Debug, Clone)]
+pub struct EnemyLeash {
+    distance: f32,
+}
+
+/// Caps the number of enemies alive at once.
+#[derive(Resource, Debug, Clone)]
+pub struct SpawnBudget {
+    pub max_enemies: usize,
+    pub recycle: RecycleStrategy,
+}
 
 #[derive(Component)]
 pub struct Enemy {
@@ -83,27 +161,46 @@ pub struct Enemy {
     pub stats: EnemyStats,
 }
 
-#[derive(Component)]
-pub enum EnemyType {
-    Undead,
-    Orc,
-    Demon,
+/// Race id and tier of an enemy, keys into `EnemiesData`.
+#[derive(Component, Debug, Clone, PartialEq)]
+pub struct EnemyType {
+    pub race: String,
+    pub tier: String,
 }
 
 pub struct EnemyPlugin;
 
-impl Plugin for EnemyPlugin {
+/// Loads `enemies.json` and `enemy_spawns.json` into `EnemiesDataResource` and
+/// `SpawnDataResource`. Headless runs insert those resources directly instead.
+pub struct EnemyDataPlugin;
+
+impl Plugin for EnemyDataPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(Startup, load_json_resources)
+            .add_systems(OnExit(GameState::Loading), build_enemy_data);
+    }
+}
+
+impl Plugin for EnemyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<NewLoopEvent>()
+            .add_event::<EnemyTeleportEvent>()
+            .init_resource::<SpawnBudget>()
+            .init_resource::<EnemyLeash>()
             .add_systems(OnEnter(GameState::InGame), setup)
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
-                    spawn_enemies_system,
-                    update_enemy_transform,
-                    despawn_dead_enemies,
-                )
-                    .run_if(in_state(GameState::InGame)),
+                    (
+                        spawn_enemies_system,
+                        track_spawn_loop,
+                        update_enemy_transform,
+                        leash_enemies,
+                    )
+                        .chain()
+                        .in_set(SimSet::Movement),
+                    despawn_dead_enemies.in_set(SimSet::Cleanup),
+                ),
             );
     }
 }
@@ -115,33 +212,39 @@ fn load_json_resources(mut commands: Commands, asset_server: Res<AssetServer>) {
     commands.insert_resource(enemies_spawn_data_handle);
 }
 
-fn setup(
+fn build_enemy_data(
     mut commands: Commands,
     enemies_data_handle: Res<EnemiesDataHandle>,
     enemies_data_assets: Res<Assets<EnemiesData>>,
     spawn_data_handle: Res<EnemiesSpawnDataHandle>,
     spawn_data_assets: Res<Assets<SpawnData>>,
 ) {
-    if let Some(enemies_data) = enemies_data_assets.get(&enemies_data_handle.0) {
-        commands.insert_resource(EnemiesDataResource(enemies_data.clone()));
-    }
+    let Some(enemies_data) = enemies_data_assets.get(&enemies_data_handle.0) else {
+        error!("Enemy data is not loaded, no enemies will spawn");
+        return;
+    };
+    commands.insert_resource(EnemiesDataResource(enemies_data.clone()));
 
     if let Some(spawn_data) = spawn_data_assets.get(&spawn_data_handle.0) {
         let mut spawn_data = spawn_data.clone();
-        let mut max_spawn_time = 0.0;
-        for spawn in &mut spawn_data.enemy_spawns {
-            spawn.id = Uuid::new_v4().to_string();
-            let end_seconds = parse_time_to_seconds(&spawn.end_time);
-            if end_seconds > max_spawn_time {
-                max_spawn_time = end_seconds;
+        if let Err(errors) = enemies_data.validate(&spawn_data) {
+            for e in errors.iter() {
+                error!("Skipping invalid enemy spawn: {}", e);
             }
+            let mut entry = 0;
+            spawn_data.enemy_spawns.retain(|_| {
+                let valid = errors.iter().all(|e| e.entry() != entry);
+                entry += 1;
+                valid
+            });
         }
-
-        commands.insert_resource(EnemyMaxSpawnTime(max_spawn_time));
-        commands.insert_resource(SpawnDataResource(spawn_data.clone()));
+        commands.insert_resource(SpawnDataResource::new(spawn_data));
     }
+}
 
+fn setup(mut commands: Commands) {
     commands.insert_resource(GameTime(0.0));
+    commands.insert_resource(SpawnLoop::default());
     commands.insert_resource(SpawnTimer(Timer::new(
         Duration::from_secs_f32(0.1),
         TimerMode::Repeating,
@@ -149,6 +252,7 @@ fn setup(
     commands.insert_resource(SpawnTracker(HashMap::new()));
 }
 
+#[allow(clippy::too_many_arguments)]
 fn spawn_enemies_system(
     mut commands: Commands,
     time: Res<Time>,
@@ -157,9 +261,11 @@ fn spawn_enemies_system(
     spawn_data: Res<SpawnDataResource>,
     mut spawn_timer: ResMut<SpawnTimer>,
     mut spawn_tracker: ResMut<SpawnTracker>,
-    enemy_texture_atlas: Res<EnemyTextureAtlas>,
-    player_query: Query<&Transform, With<Player>>,
-    max_spawn_time: Res<EnemyMaxSpawnTime>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    mut enemy_query: Query<RecyclableEnemy>,
+    spawn_budget: Res<SpawnBudget>,
+    mut run_rng: ResMut<RunRng>,
+    mut teleports: EventWriter<EnemyTeleportEvent>,
 ) {
     // Update game time
     game_time.0 += time.delta_seconds();
@@ -173,111 +279,226 @@ fn spawn_enemies_system(
     }
 
     let player_pos = player_query.single().translation.truncate();
-    let max_spawn_time = max_spawn_time.0;
 
     // Check if it's time to attempt spawning
     if spawn_timer.0.just_finished() {
-        let spawn_multiplier = (current_game_time / max_spawn_time).floor();
-
-        for spawn in &spawn_data.0.enemy_spawns {
-            let start_seconds =
-                parse_time_to_seconds(&spawn.start_time) + (max_spawn_time * spawn_multiplier);
-            let end_seconds =
-                parse_time_to_seconds(&spawn.end_time) + (max_spawn_time * spawn_multiplier);
-
-            if current_game_time >= start_seconds && current_game_time <= end_seconds {
+        let timeline = SpawnTimeline::new(&spawn_data.0);
+        let spawn_multiplier = timeline.loop_index(current_game_time);
+        let mut scheduled = Vec::new();
+
+        for spawn in timeline.spawns() {
+            if let Some((start_seconds, end_seconds)) =
+                timeline.active_window(spawn, current_game_time)
+            {
                 let spawn_id = format!("{}_{}", spawn.id, spawn_multiplier);
                 let total_to_spawn =
                     calculate_spawn_count(spawn, start_seconds, end_seconds, current_game_time);
                 let already_spawned = spawn_tracker.0.entry(spawn_id).or_insert(0);
                 let new_spawns = total_to_spawn.saturating_sub(*already_spawned);
 
-                let enemy_stats = enemy_data
-                    .0
-                    .get_enemy_stats(&spawn.race, &spawn.power)
-                    .unwrap();
+                // Entries are validated when the data is loaded.
+                let Some(tier) = enemy_data.0.tier(&spawn.race, &spawn.power) else {
+                    continue;
+                };
 
                 for _ in 0..new_spawns {
-                    let (x, y) = get_random_position_around(player_pos);
-                    let enemy_type = EnemyType::get_race(&spawn.race);
-                    let animation_indicies =
-                        EnemyType::get_animation_indicies(&enemy_type, &spawn.power);
-                    // Spawn enemy
-                    let enemy_stats = enemy_stats.clone();
-                    commands.spawn(EnemyBundle::new(
-                        &enemy_texture_atlas,
-                        enemy_type,
-                        animation_indicies,
-                        vec3(x, y, 1.0),
-                        EnemyStats {
-                            health: enemy_stats.health
-                                + (enemy_stats.health * (1.2 * spawn_multiplier).floor() as u32),
-                            damage: enemy_stats.damage,
-                            power: enemy_stats.power,
-                        },
-                    ));
+                    let (x, y) = get_random_position_around(player_pos, &mut *run_rng);
+                    let enemy_type = EnemyType {
+                        race: spawn.race.clone(),
+                        tier: spawn.power.clone(),
+                    };
+                    let stats = EnemyStats {
+                        health: tier.stats.health * loop_health_multiplier(spawn_multiplier),
+                        damage: tier.stats.damage,
+                        power: tier.stats.power,
+                    };
+                    scheduled.push((enemy_type, vec3(x, y, 1.0), stats, tier.hitbox));
                 }
 
                 *already_spawned = total_to_spawn;
             }
         }
+
+        // Spawns that don't fit under the cap take over existing enemies
+        let free = spawn_budget
+            .max_enemies
+            .saturating_sub(enemy_query.iter().count());
+        let over_budget = scheduled.split_off(free.min(scheduled.len()));
+        for (enemy_type, position, stats, hitbox) in scheduled {
+            commands.spawn(EnemyBundle::new(enemy_type, position, stats, hitbox));
+        }
+        recycle_enemies(
+            &mut enemy_query,
+            player_pos,
+            spawn_budget.recycle,
+            over_budget,
+            &mut teleports,
+        );
     }
 }
 
-fn parse_time_to_seconds(time_str: &str) -> f32 {
-    let parts: Vec<&str> = time_str.split(':').collect();
-    let minutes: f32 = parts[0].parse().unwrap_or(0.0);
-    let seconds: f32 = parts[1].parse().unwrap_or(0.0);
-    minutes * 60.0 + seconds
+#[derive(QueryData)]
+#[query_data(mutable)]
+struct RecyclableEnemy {
+    entity: Entity,
+    enemy: &'static mut Enemy,
+    enemy_type: &'static mut EnemyType,
+    hitbox: &'static mut Hitbox,
+    transform: &'static mut Transform,
+    interpolated: &'static mut InterpolatedTransform,
 }
 
-fn calculate_spawn_count(spawn: &EnemySpawn, start: f32, end: f32, current: f32) -> u32 {
-    let total_time = end - start;
-    let elapsed_time = current - start;
-    let spawn_ratio = elapsed_time / total_time;
-    let total_to_spawn = (spawn.count as f32 * spawn_ratio) as u32;
-    total_to_spawn
+/// Turns existing enemies into the given spawns, picked by `strategy`.
+fn recycle_enemies(
+    enemy_query: &mut Query<RecyclableEnemy>,
+    player_pos: Vec2,
+    strategy: RecycleStrategy,
+    spawns: Vec<(EnemyType, Vec3, EnemyStats, Hitbox)>,
+    teleports: &mut EventWriter<EnemyTeleportEvent>,
+) {
+    if spawns.is_empty() {
+        return;
+    }
+
+    // Lowest key is recycled first, entity breaks ties to keep runs reproducible
+    let mut candidates: Vec<(f32, f32, Entity)> = match strategy {
+        RecycleStrategy::Drop => return,
+        RecycleStrategy::Farthest => enemy_query
+            .iter()
+            .map(|enemy| {
+                let distance = enemy.transform.translation.truncate().distance(player_pos);
+                (-distance, 0.0, enemy.entity)
+            })
+            .collect(),
+        RecycleStrategy::Weakest => enemy_query
+            .iter()
+            .map(|enemy| {
+                (
+                    enemy.enemy.stats.power as f32,
+                    enemy.enemy.current_health,
+                    enemy.entity,
+                )
+            })
+            .collect(),
+    };
+    candidates.sort_by(|a, b| {
+        a.0.total_cmp(&b.0)
+            .then(a.1.total_cmp(&b.1))
+            .then(a.2.cmp(&b.2))
+    });
+
+    for ((_, _, entity), (enemy_type, position, stats, hitbox)) in
+        candidates.into_iter().zip(spawns)
+    {
+        let Ok(mut enemy) = enemy_query.get_mut(entity) else {
+            continue;
+        };
+        *enemy.enemy = Enemy::new(stats);
+        *enemy.hitbox = hitbox;
+        if *enemy.enemy_type != enemy_type {
+            *enemy.enemy_type = enemy_type;
+        }
+        enemy.transform.translation = position;
+        enemy.interpolated.teleport();
+        teleports.send(EnemyTeleportEvent(entity));
+    }
+}
+
+fn track_spawn_loop(
+    game_time: Res<GameTime>,
+    spawn_data: Res<SpawnDataResource>,
+    mut spawn_loop: ResMut<SpawnLoop>,
+    mut ew: EventWriter<NewLoopEvent>,
+) {
+    let loop_index = SpawnTimeline::new(&spawn_data.0).loop_index(game_time.0);
+    if loop_index > spawn_loop.0 {
+        spawn_loop.0 = loop_index;
+        ew.send(NewLoopEvent {
+            loop_index,
+            health_multiplier: loop_health_multiplier(loop_index),
+        });
+    }
 }
 
 fn despawn_dead_enemies(
     mut commands: Commands,
-    enemy_query: Query<(&Enemy, Entity), With<Enemy>>,
-    mut experience_query: Query<&mut Experience, With<Player>>,
+    enemy_query: Query<(&Enemy, &EnemyType, Entity), With<Enemy>>,
+    mut experience_query: Query<(&mut Experience, &ExperienceMultiplier), With<Player>>,
     mut score: ResMut<Score>,
+    mut run_stats: ResMut<RunStats>,
+    enemy_data: Res<EnemiesDataResource>,
 ) {
     if enemy_query.is_empty() || experience_query.is_empty() {
         return;
     }
-    let mut experience = experience_query.single_mut();
+    let (mut experience, multiplier) = experience_query.single_mut();
 
-    for (enemy, entity) in enemy_query.iter() {
+    for (enemy, enemy_type, entity) in enemy_query.iter() {
         if enemy.current_health <= 0.0 {
-            experience.0 += 1.0;
-            score.0 += 1 * enemy.stats.power;
+            experience.0 += multiplier.0;
+            run_stats.experience_earned += multiplier.0;
+            score.0 += enemy.stats.power;
+            *run_stats
+                .kills
+                .entry(enemy_data.0.display_name(&enemy_type.race).to_string())
+                .or_insert(0) += 1;
             commands.entity(entity).despawn();
         }
     }
 }
 
-fn update_enemy_transform(
+pub fn update_enemy_transform(
     player_query: Query<&Transform, With<Player>>,
     mut enemy_query: Query<&mut Transform, (With<Enemy>, Without<Player>)>,
+    time: Res<Time>,
 ) {
     if player_query.is_empty() || enemy_query.is_empty() {
         return;
     }
 
     let player_pos = player_query.single().translation;
+    let step = ENEMY_SPEED * time.delta_seconds();
     for mut transform in enemy_query.iter_mut() {
         let dir = (player_pos - transform.translation).normalize();
-        transform.translation += dir * ENEMY_SPEED;
+        transform.translation += dir * step;
     }
 }
 
-fn get_random_position_around(pos: Vec2) -> (f32, f32) {
-    let mut rng = rand::thread_rng();
+pub fn leash_enemies(
+    leash: Res<EnemyLeash>,
+    player_query: Query<&Transform, (With<Player>, Without<Enemy>)>,
+    mut enemy_query: Query<(Entity, &mut Transform, &mut InterpolatedTransform), With<Enemy>>,
+    mut run_rng: ResMut<RunRng>,
+    mut run_stats: ResMut<RunStats>,
+    mut teleports: EventWriter<EnemyTeleportEvent>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let player_pos = player_transform.translation.truncate();
+    let max_distance_squared = leash.distance * leash.distance;
+    for (entity, mut transform, mut interpolated) in enemy_query.iter_mut() {
+        if transform
+            .translation
+            .truncate()
+            .distance_squared(player_pos)
+            <= max_distance_squared
+        {
+            continue;
+        }
+        let (x, y) = get_random_position_around(player_pos, &mut *run_rng);
+        transform.translation.x = x;
+        transform.translation.y = y;
+        interpolated.teleport();
+        teleports.send(EnemyTeleportEvent(entity));
+        run_stats.enemies_leashed += 1;
+    }
+}
+
+fn get_random_position_around(pos: Vec2, rng: &mut impl Rng) -> (f32, f32) {
     let angle = rng.gen_range(0.0..PI * 2.0);
-    let dist = rng.gen_range(1000.0..5000.0);
+    let dist = rng.gen_range(ENEMY_SPAWN_RING_MIN..ENEMY_SPAWN_RING_MAX);
 
     let offset_x = angle.cos() * dist;
     let offset_y = angle.sin() * dist;
@@ -288,6 +509,50 @@ fn get_random_position_around(pos: Vec2) -> (f32, f32) {
     (random_x, random_y)
 }
 
+impl SpawnDataResource {
+    /// Gives every spawn a unique id so its progress can be tracked per loop.
+    pub fn new(mut spawn_data: SpawnData) -> Self {
+        for spawn in &mut spawn_data.enemy_spawns {
+            spawn.id = Uuid::new_v4().to_string();
+        }
+        Self(spawn_data)
+    }
+}
+
+impl EnemyLeash {
+    pub fn new(distance: f32) -> anyhow::Result<Self> {
+        if distance.is_nan() || distance <= ENEMY_SPAWN_RING_MAX {
+            anyhow::bail!(
+                "leash distance {} must be beyond the spawn ring ({})",
+                distance,
+                ENEMY_SPAWN_RING_MAX
+            );
+        }
+        Ok(Self { distance })
+    }
+
+    pub fn distance(&self) -> f32 {
+        self.distance
+    }
+}
+
+impl Default for EnemyLeash {
+    fn default() -> Self {
+        Self {
+            distance: ENEMY_LEASH_DISTANCE,
+        }
+    }
+}
+
+impl Default for SpawnBudget {
+    fn default() -> Self {
+        Self {
+            max_enemies: MAX_NUM_ENEMIES,
+            recycle: RecycleStrategy::default(),
+        }
+    }
+}
+
 impl Enemy {
     pub fn new(stats: EnemyStats) -> Self {
         Self {
@@ -297,89 +562,101 @@ impl Enemy {
     }
 }
 
-impl EnemyType {
-    fn get_race(race: &str) -> Self {
-        match race {
-            "Undead" => Self::Undead,
-            "Orc" => Self::Orc,
-            "Demon" => Self::Demon,
-            _ => Self::Undead,
+impl EnemiesData {
+    pub fn race(&self, id: &str) -> Option<&EnemyRace> {
+        self.races.get(id)
+    }
+
+    pub fn tier(&self, race: &str, tier: &str) -> Option<&EnemyTier> {
+        self.race(race)?.tiers.get(tier)
+    }
+
+    /// Display name of a race, or its id if the race is unknown.
+    pub fn display_name<'a>(&'a self, race: &'a str) -> &'a str {
+        self.race(race)
+            .map_or(race, |definition| definition.display_name.as_str())
+    }
+
+    /// Checks that every spawn entry refers to a known race and tier, and that
+    /// the tier's hitbox fits within `MAX_ENEMY_HITBOX_REACH`.
+    pub fn validate(&self, spawn_data: &SpawnData) -> Result<(), Vec<SpawnDataError>> {
+        let errors: Vec<SpawnDataError> = spawn_data
+            .enemy_spawns
+            .iter()
+            .enumerate()
+            .filter_map(|(entry, spawn)| {
+                let Some(race) = self.race(&spawn.race) else {
+                    return Some(SpawnDataError::UnknownRace {
+                        entry,
+                        race: spawn.race.clone(),
+                    });
+                };
+                let Some(tier) = race.tiers.get(&spawn.power) else {
+                    return Some(SpawnDataError::UnknownTier {
+                        entry,
+                        race: spawn.race.clone(),
+                        tier: spawn.power.clone(),
+                    });
+                };
+                let reach = tier.hitbox_reach();
+                (reach > MAX_ENEMY_HITBOX_REACH).then(|| SpawnDataError::HitboxTooLarge {
+                    entry,
+                    race: spawn.race.clone(),
+                    tier: spawn.power.clone(),
+                    reach,
+                })
+            })
+            .collect();
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
     }
+}
+
+impl EnemyTier {
+    /// Farthest the hitbox reaches from the enemy's position, in world units.
+    pub fn hitbox_reach(&self) -> f32 {
+        self.hitbox.reach() * SPRITE_SCALE_FACTOR
+    }
+}
 
-    pub fn get_animation_indicies(&self, level: &str) -> AnimationIndices {
+impl SpawnDataError {
+    /// Index of the offending entry in `enemy_spawns`.
+    pub fn entry(&self) -> usize {
         match self {
-            EnemyType::Undead => match level {
-                "1" => AnimationIndices {
-                    first: 52,
-                    last: 55,
-                },
-                "2" => AnimationIndices {
-                    first: 56,
-                    last: 59,
-                },
-                "3" => AnimationIndices {
-                    first: 48,
-                    last: 51,
-                },
-                "4" => AnimationIndices {
-                    first: 44,
-                    last: 47,
-                },
-                "5" => AnimationIndices {
-                    first: 40,
-                    last: 43,
-                },
-                _ => AnimationIndices { first: 0, last: 0 },
-            },
-            EnemyType::Orc => match level {
-                "1" => AnimationIndices {
-                    first: 20,
-                    last: 23,
-                },
-                "2" => AnimationIndices {
-                    first: 36,
-                    last: 39,
-                },
-                "3" => AnimationIndices {
-                    first: 32,
-                    last: 35,
-                },
-                "4" => AnimationIndices {
-                    first: 24,
-                    last: 27,
-                },
-                "5" => AnimationIndices {
-                    first: 28,
-                    last: 31,
-                },
-                _ => AnimationIndices { first: 0, last: 0 },
-            },
-            EnemyType::Demon => match level {
-                "1" => AnimationIndices { first: 8, last: 11 },
-                "2" => AnimationIndices {
-                    first: 16,
-                    last: 19,
-                },
-                "3" => AnimationIndices { first: 4, last: 7 },
-                "4" => AnimationIndices {
-                    first: 12,
-                    last: 15,
-                },
-                "5" => AnimationIndices { first: 0, last: 3 },
-                _ => AnimationIndices { first: 0, last: 0 },
-            },
+            SpawnDataError::UnknownRace { entry, .. }
+            | SpawnDataError::UnknownTier { entry, .. }
+            | SpawnDataError::HitboxTooLarge { entry, .. } => *entry,
         }
     }
 }
 
-impl EnemiesData {
-    pub fn get_enemy_stats(&self, race: &str, level: &str) -> Option<&EnemyStats> {
-        match race.to_lowercase().as_str() {
-            "undead" => self.undead.levels.get(level),
-            "orc" => self.orc.levels.get(level),
-            "demon" => self.demon.levels.get(level),
-            _ => None,
+impl fmt::Display for SpawnDataError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpawnDataError::UnknownRace { entry, race } => {
+                write!(f, "spawn entry {} uses unknown race '{}'", entry, race)
+            }
+            SpawnDataError::UnknownTier { entry, race, tier } => write!(
+                f,
+                "spawn entry {} uses unknown tier '{}' of race '{}'",
+                entry, tier, race
+            ),
+            SpawnDataError::HitboxTooLarge {
+                entry,
+                race,
+                tier,
+                reach,
+            } => write!(
+                f,
+                "spawn entry {} uses tier '{}' of race '{}', whose hitbox reaches {} past the limit of {}",
+                entry, tier, race, reach, MAX_ENEMY_HITBOX_REACH
+            ),
         }
     }
 }
+
+impl std::error::Error for SpawnDataError {}
@@ -1,47 +1,36 @@
 use bevy::ecs::bundle::Bundle;
 use bevy::prelude::*;
 
-use crate::animation::AnimationTimer;
+use crate::collision::{CollisionLayer, Hitbox};
 use crate::enemy::{Enemy, EnemyStats, EnemyType};
+use crate::simulation::InterpolatedTransform;
 use crate::world::GameEntity;
 use crate::*;
 
-use self::animation::AnimationIndices;
-use self::enemy_textures::EnemyTextureAtlas;
-
+/// Simulation side of an enemy. Sprites are attached separately by
+/// `EnemyTexturesPlugin`, so this also spawns fine in headless runs.
 #[derive(Bundle)]
 pub struct EnemyBundle {
-    sprite_sheet: SpriteSheetBundle,
+    transform: TransformBundle,
     enemy: Enemy,
     enemy_type: EnemyType,
-    animation_indicies: AnimationIndices,
-    animation_timer: AnimationTimer,
+    hitbox: Hitbox,
+    collision_layer: CollisionLayer,
+    interpolated: InterpolatedTransform,
     game_entity: GameEntity,
 }
 
 impl EnemyBundle {
-    pub fn new(
-        handle: &EnemyTextureAtlas,
-        enemy_type: EnemyType,
-        animation_indicies: AnimationIndices,
-        position: Vec3,
-        stats: EnemyStats,
-    ) -> Self {
+    pub fn new(enemy_type: EnemyType, position: Vec3, stats: EnemyStats, hitbox: Hitbox) -> Self {
         Self {
-            sprite_sheet: SpriteSheetBundle {
-                texture: handle.image.clone().unwrap(),
-                atlas: TextureAtlas {
-                    layout: handle.layout.clone().unwrap(),
-                    index: animation_indicies.first,
-                },
-                transform: Transform::from_translation(position)
-                    .with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
-                ..default()
-            },
+            transform: TransformBundle::from_transform(
+                Transform::from_translation(position).with_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+            ),
             enemy: Enemy::new(stats),
             enemy_type,
-            animation_indicies,
-            animation_timer: AnimationTimer(Timer::from_seconds(0.08, TimerMode::Repeating)),
+            hitbox,
+            collision_layer: CollisionLayer::Enemy,
+            interpolated: InterpolatedTransform::default(),
             game_entity: GameEntity,
         }
     }
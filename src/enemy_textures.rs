@@ -1,11 +1,17 @@
+use std::collections::HashMap;
+
+use crate::animation::{AnimationClip, AnimationPlayer};
+use crate::enemy::{EnemiesDataResource, EnemyType};
 use crate::generate_texture_atlas::SerializableTextureAtlasLayout;
 use crate::state::GameState;
 use bevy::prelude::*;
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct EnemyTextureAtlas {
     pub layout: Option<Handle<TextureAtlasLayout>>,
     pub image: Option<Handle<Image>>,
+    /// Clip of every tier in `enemies.json` by race and tier, resolved when the atlas loads.
+    pub clips: HashMap<(String, String), AnimationClip>,
 }
 
 #[derive(Resource)]
@@ -17,7 +23,11 @@ impl Plugin for EnemyTexturesPlugin {
     fn build(&self, app: &mut App) {
         app.init_resource::<EnemyTextureAtlas>()
             .add_systems(Startup, load_json_resources)
-            .add_systems(OnEnter(GameState::MainMenu), setup_texture_atlas_from_files);
+            .add_systems(OnEnter(GameState::MainMenu), setup_texture_atlas_from_files)
+            .add_systems(
+                Update,
+                attach_enemy_sprites.run_if(in_state(GameState::InGame)),
+            );
     }
 }
 
@@ -33,6 +43,7 @@ fn setup_texture_atlas_from_files(
     mut enemy_texture_atlas: ResMut<EnemyTextureAtlas>,
     enemy_texture_atlas_handle: Res<EnemyTextureAtlasHandle>,
     serializable_layouts: Res<Assets<SerializableTextureAtlasLayout>>,
+    enemies_data: Option<Res<EnemiesDataResource>>,
 ) {
     // Load the pre-generated texture atlas image
     let texture_handle: Handle<Image> = asset_server.load("enemy_textures.png");
@@ -51,14 +62,53 @@ fn setup_texture_atlas_from_files(
         // Update the HitTextureAtlas resource
         enemy_texture_atlas.image = Some(texture_handle);
         enemy_texture_atlas.layout = Some(layout_handle);
+
+        enemy_texture_atlas.clips.clear();
+        let tiers = enemies_data
+            .iter()
+            .flat_map(|enemies_data| enemies_data.0.races.iter())
+            .flat_map(|(race, definition)| definition.tiers.iter().map(move |tier| (race, tier)));
+        for (race, (tier_id, tier)) in tiers {
+            match tier.clip.resolve(layout) {
+                Ok(clip) => {
+                    enemy_texture_atlas
+                        .clips
+                        .insert((race.clone(), tier_id.clone()), clip);
+                }
+                Err(e) => error!("Failed to load sprite of enemy {} {}: {}", race, tier_id, e),
+            }
+        }
     }
 }
 
-impl Default for EnemyTextureAtlas {
-    fn default() -> Self {
-        Self {
-            layout: None,
-            image: None,
-        }
+/// Gives new enemies their sprite, and recycled enemies the sprite of their new type.
+fn attach_enemy_sprites(
+    mut commands: Commands,
+    enemy_texture_atlas: Res<EnemyTextureAtlas>,
+    enemy_query: Query<(Entity, &EnemyType), Changed<EnemyType>>,
+) {
+    let (Some(image), Some(layout)) = (&enemy_texture_atlas.image, &enemy_texture_atlas.layout)
+    else {
+        return;
+    };
+
+    for (entity, enemy_type) in enemy_query.iter() {
+        let Some(clip) = enemy_texture_atlas
+            .clips
+            .get(&(enemy_type.race.clone(), enemy_type.tier.clone()))
+        else {
+            continue;
+        };
+
+        commands.entity(entity).insert((
+            Sprite::default(),
+            image.clone(),
+            TextureAtlas {
+                layout: layout.clone(),
+                index: clip.frames.first,
+            },
+            VisibilityBundle::default(),
+            AnimationPlayer::new(clip.clone()),
+        ));
     }
 }
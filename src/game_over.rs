@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+
+use crate::gui::high_score_lines;
+use crate::meta_progression::MetaUpgrade;
+use crate::resources::{RunRng, RunStats, Score};
+use crate::save::{record_run, LastRun, SaveData};
+use crate::state::GameState;
+use crate::utils::format_time;
+
+pub struct GameOverPlugin;
+
+#[derive(Component)]
+struct GameOverItem;
+
+#[derive(Component)]
+enum GameOverButton {
+    Retry,
+    MainMenu,
+}
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::GameOver),
+            setup_game_over_screen.after(record_run),
+        )
+        .add_systems(OnExit(GameState::GameOver), despawn_game_over_screen)
+        .add_systems(
+            Update,
+            handle_game_over_buttons.run_if(in_state(GameState::GameOver)),
+        );
+    }
+}
+
+fn setup_game_over_screen(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    save_data: Res<SaveData>,
+    last_run: Res<LastRun>,
+    run_rng: Res<RunRng>,
+) {
+    let font = asset_server.load("monogram.ttf");
+    let line_style = TextStyle {
+        font: font.clone(),
+        font_size: 36.0,
+        color: Color::WHITE,
+    };
+
+    let mut lines = vec![
+        format!("Score: {}", score.0),
+        format!("Time survived: {}", format_time(run_stats.time_survived)),
+        format!("Level reached: {}", run_stats.level_reached),
+        format!("Kills: {}", run_stats.total_kills()),
+    ];
+    for (race, kills) in run_stats.kills.iter() {
+        lines.push(format!("  {}: {}", race, kills));
+    }
+    lines.push(format!("Damage dealt: {:.0}", run_stats.damage_dealt));
+    lines.push(if run_stats.upgrades_taken.is_empty() {
+        "Upgrades: none".to_string()
+    } else {
+        let upgrades: Vec<String> = run_stats
+            .upgrades_taken
+            .iter()
+            .map(|(id, level)| {
+                let name = MetaUpgrade::from_id(id).map_or(id.as_str(), |upgrade| upgrade.name());
+                format!("{} {}", name, level)
+            })
+            .collect();
+        format!("Upgrades: {}", upgrades.join(", "))
+    });
+    lines.push(format!("Seed: {}", run_rng.seed()));
+    if let Some(record) = &last_run.0 {
+        lines.push(format!(
+            "Gold earned: {} (total {})",
+            record.currency_earned, save_data.meta.currency
+        ));
+        lines.push(String::new());
+        lines.push(format!("Best runs as {}", record.hero));
+        lines.extend(high_score_lines(
+            &save_data,
+            &record.hero,
+            Some(record.run_number),
+        ));
+    }
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    ..default()
+                },
+                background_color: BackgroundColor::from(Color::BLACK.with_a(0.9)),
+                ..default()
+            },
+            GameOverItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn(
+                TextBundle::from_section(
+                    "Game Over",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 96.0,
+                        color: Color::ORANGE_RED,
+                    },
+                )
+                .with_style(Style {
+                    margin: UiRect::px(0.0, 0.0, 0.0, 20.0),
+                    ..default()
+                }),
+            );
+
+            for line in lines {
+                parent.spawn(TextBundle::from_section(line, line_style.clone()));
+            }
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Row,
+                        margin: UiRect::px(0.0, 0.0, 30.0, 0.0),
+                        column_gap: Val::Px(20.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    spawn_button(parent, "Retry", GameOverButton::Retry);
+                    spawn_button(parent, "Main Menu", GameOverButton::MainMenu);
+                });
+        });
+}
+
+fn spawn_button(parent: &mut ChildBuilder, label: &str, button: GameOverButton) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(220.0),
+                    height: Val::Px(65.0),
+                    border: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_color: BorderColor(Color::BLACK),
+                ..default()
+            },
+            button,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::BLACK,
+                    ..default()
+                },
+            ));
+        });
+}
+
+fn handle_game_over_buttons(
+    interaction_query: Query<(&Interaction, &GameOverButton), Changed<Interaction>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            GameOverButton::Retry => next_state.set(GameState::GameInit),
+            GameOverButton::MainMenu => next_state.set(GameState::MainMenu),
+        }
+    }
+}
+
+fn despawn_game_over_screen(
+    mut commands: Commands,
+    game_over_items_query: Query<Entity, With<GameOverItem>>,
+) {
+    for e in game_over_items_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
@@ -1,177 +1,199 @@
-use crate::state::GameState;
-use bevy::asset::LoadedFolder;
-use bevy::prelude::*;
-use bevy::render::texture::ImageSampler;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
 
-use image::ImageFormat;
+use bevy::prelude::*;
+use bevy::render::render_asset::RenderAssetUsages;
+use image::DynamicImage;
 use serde::{Deserialize, Serialize};
 
-use serde_json;
-use std::fs::File;
-use std::io::Write;
-
-#[derive(Resource, Default)]
-struct HitSpriteFolder(Handle<LoadedFolder>);
-
-#[derive(Resource, Default)]
-struct EnemySpriteFolder(Handle<LoadedFolder>);
+use crate::animation::AnimationIndices;
 
 #[derive(Serialize, Deserialize, Asset, TypePath)]
 pub struct SerializableTextureAtlasLayout {
     pub size: Vec2,
     pub textures: Vec<Rect>,
+    /// Name of each frame, its source path without the extension relative to
+    /// the sprite folder, e.g. `undead/zombie/zombie_anim_f0`.
+    #[serde(default)]
+    pub names: Vec<String>,
 }
 
-impl From<&TextureAtlasLayout> for SerializableTextureAtlasLayout {
-    fn from(layout: &TextureAtlasLayout) -> Self {
+#[derive(Debug, Clone, PartialEq)]
+pub enum ClipError {
+    /// No frame lives under the clip's folder.
+    Missing(String),
+    /// The clip's frames are not next to each other in the atlas.
+    NotContiguous(String),
+    /// The clip's frame rate is not a positive number.
+    BadFrameRate(String),
+}
+
+impl SerializableTextureAtlasLayout {
+    pub fn new(layout: &TextureAtlasLayout, names: Vec<String>) -> Self {
         Self {
             size: layout.size,
             textures: layout.textures.clone(),
+            names,
         }
     }
-}
 
-pub struct GenerateTextureAtlasPlugin;
+    pub fn frame(&self, name: &str) -> Option<usize> {
+        self.names.iter().position(|frame| frame == name)
+    }
+
+    /// Frames of a clip, played in [`frame_order`]. A clip is either the
+    /// folder its frames were packed from (e.g. `undead/zombie`) or the name
+    /// its numbered frames share (e.g. `wizard/m/wizzard_m_run_anim` for
+    /// `wizzard_m_run_anim_f0` to `_f3`), for folders holding several clips.
+    pub fn clip(&self, clip: &str) -> Result<AnimationIndices, ClipError> {
+        let mut frames: Vec<(&String, usize)> = self
+            .names
+            .iter()
+            .enumerate()
+            .filter(|(_, name)| in_clip(name, clip))
+            .map(|(index, name)| (name, index))
+            .collect();
+        frames.sort_by(|a, b| frame_order(a.0).cmp(&frame_order(b.0)));
+
+        let (Some((_, first)), Some((_, last))) = (frames.first(), frames.last()) else {
+            return Err(ClipError::Missing(clip.to_string()));
+        };
+        let contiguous = frames
+            .iter()
+            .enumerate()
+            .all(|(offset, (_, index))| *index == first + offset);
+        if !contiguous {
+            return Err(ClipError::NotContiguous(clip.to_string()));
+        }
 
-impl Plugin for GenerateTextureAtlasPlugin {
-    fn build(&self, app: &mut App) {
-        app.add_systems(Startup, load_folders)
-            .add_systems(OnEnter(GameState::GameInit), generate_enemy_textures)
-            .add_systems(OnEnter(GameState::GameInit), generate_hit_textures);
+        Ok(AnimationIndices {
+            first: *first,
+            last: *last,
+        })
     }
 }
-fn load_folders(mut commands: Commands, asset_server: Res<AssetServer>) {
-    commands.insert_resource(HitSpriteFolder(asset_server.load_folder("hits")));
-    commands.insert_resource(EnemySpriteFolder(asset_server.load_folder("enemies")));
+
+fn in_clip(name: &str, clip: &str) -> bool {
+    let Some(rest) = name.strip_prefix(clip) else {
+        return false;
+    };
+    if rest.starts_with('/') {
+        return true;
+    }
+    rest.strip_prefix("_f").is_some_and(is_frame_number)
 }
 
-fn generate_enemy_textures(
-    mut textures: ResMut<Assets<Image>>,
-    loaded_folders: Res<Assets<LoadedFolder>>,
-    sprite_handles: Res<EnemySpriteFolder>,
-) {
-    let loaded_folder = loaded_folders.get(&sprite_handles.0).unwrap();
-    let (texture_atlas_linear, linear_texture) = create_texture_atlas(
-        loaded_folder,
-        None,
-        Some(ImageSampler::nearest()),
-        &mut textures,
-    );
-
-    // Save the texture atlas image to a file
-    if let Some(image) = textures.get(&linear_texture) {
-        let buffer = image.clone().try_into_dynamic().unwrap();
-        buffer
-            .save_with_format("assets/enemy_textures.png", ImageFormat::Png)
-            .unwrap();
-        println!("Texture atlas saved to assets/enemy_textures.png");
+/// Sort key for frame names: by the name numbered frames share, then by frame
+/// number, so `zombie_anim_f10` comes after `zombie_anim_f9`.
+pub fn frame_order(name: &str) -> (&str, Option<u64>, &str) {
+    match name.rsplit_once("_f") {
+        Some((shared, number)) if is_frame_number(number) => (shared, number.parse().ok(), name),
+        _ => (name, None, name),
     }
+}
 
-    let serializable_layout = SerializableTextureAtlasLayout::from(&texture_atlas_linear);
-    let layout_json = serde_json::to_string_pretty(&serializable_layout).unwrap();
-    let mut file = File::create("assets/enemy_texture_atlas_layout.json").unwrap();
-    file.write_all(layout_json.as_bytes()).unwrap();
-    println!("Texture atlas layout saved to assets/enemy_texture_atlas_layout.json");
+fn is_frame_number(number: &str) -> bool {
+    !number.is_empty() && number.chars().all(|c| c.is_ascii_digit())
 }
 
-fn generate_hit_textures(
-    mut textures: ResMut<Assets<Image>>,
-    loaded_folders: Res<Assets<LoadedFolder>>,
-    sprite_handles: Res<HitSpriteFolder>,
-) {
-    let loaded_folder = loaded_folders.get(&sprite_handles.0).unwrap();
-    let (texture_atlas_linear, linear_texture) = create_texture_atlas(
-        loaded_folder,
-        None,
-        Some(ImageSampler::nearest()),
-        &mut textures,
-    );
-
-    // Save the texture atlas image to a file
-    if let Some(image) = textures.get(&linear_texture) {
-        let buffer = image.clone().try_into_dynamic().unwrap();
-        buffer
-            .save_with_format("assets/hit_textures.png", ImageFormat::Png)
-            .unwrap();
-        println!("Texture atlas saved to assets/hit_textures.png");
+impl fmt::Display for ClipError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ClipError::Missing(clip) => write!(f, "no frames for clip '{}'", clip),
+            ClipError::NotContiguous(clip) => {
+                write!(f, "frames of clip '{}' are not contiguous", clip)
+            }
+            ClipError::BadFrameRate(clip) => {
+                write!(f, "clip '{}' needs a positive frame rate", clip)
+            }
+        }
     }
-
-    let serializable_layout = SerializableTextureAtlasLayout::from(&texture_atlas_linear);
-    let layout_json = serde_json::to_string_pretty(&serializable_layout).unwrap();
-    let mut file = File::create("assets/hit_texture_atlas_layout.json").unwrap();
-    file.write_all(layout_json.as_bytes()).unwrap();
-    println!("Texture atlas layout saved to assets/hit_texture_atlas_layout.json");
 }
 
-fn create_texture_atlas(
-    folder: &LoadedFolder,
-    padding: Option<UVec2>,
-    sampling: Option<ImageSampler>,
-    textures: &mut ResMut<Assets<Image>>,
-) -> (TextureAtlasLayout, Handle<Image>) {
-    // Build a texture atlas using the individual sprites
-    let mut texture_atlas_builder =
-        TextureAtlasBuilder::default().padding(padding.unwrap_or_default());
-    for handle in folder.handles.iter() {
-        let id = handle.id().typed_unchecked::<Image>();
-        let Some(texture) = textures.get(id) else {
-            warn!(
-                "{:?} did not resolve to an `Image` asset.",
-                handle.path().unwrap()
-            );
-            continue;
-        };
+impl std::error::Error for ClipError {}
 
-        texture_atlas_builder.add_texture(Some(id), texture);
+/// A packed atlas image and its layout.
+pub struct PackedAtlas {
+    pub image: DynamicImage,
+    pub layout: SerializableTextureAtlasLayout,
+}
+
+/// Packs every PNG under the given folders into a single atlas. Frames are
+/// added in [`frame_order`], so packing the same files always produces the
+/// same atlas.
+pub fn pack_folders(
+    folders: &[PathBuf],
+    padding: u32,
+    max_size: u32,
+) -> anyhow::Result<PackedAtlas> {
+    let mut frames = Vec::new();
+    for folder in folders {
+        collect_frames(folder, folder, &mut frames)?;
+    }
+    frames.sort_by(|a, b| frame_order(&a.0).cmp(&frame_order(&b.0)));
+    if let Some(pair) = frames.windows(2).find(|pair| pair[0].0 == pair[1].0) {
+        anyhow::bail!("frame '{}' exists in more than one input folder", pair[0].0);
+    }
+    if frames.is_empty() {
+        anyhow::bail!("no PNG files found in the input folders");
     }
 
-    let (texture_atlas_layout, texture) = texture_atlas_builder.finish().unwrap();
-    let texture = textures.add(texture);
+    let mut images = Vec::with_capacity(frames.len());
+    for (_, path) in frames.iter() {
+        let image = image::open(path)
+            .map_err(|e| anyhow::anyhow!("failed to read {}: {}", path.display(), e))?;
+        images.push(Image::from_dynamic(
+            image,
+            true,
+            RenderAssetUsages::default(),
+        ));
+    }
 
-    // Update the sampling settings of the texture atlas
-    let image = textures.get_mut(&texture).unwrap();
-    image.sampler = sampling.unwrap_or_default();
+    let mut builder = TextureAtlasBuilder::default()
+        .padding(UVec2::splat(padding))
+        .max_size(Vec2::splat(max_size as f32));
+    for image in images.iter() {
+        builder.add_texture(None, image);
+    }
+    let (layout, image) = builder
+        .finish()
+        .map_err(|e| anyhow::anyhow!("failed to pack atlas: {:?}", e))?;
+    let image = image
+        .try_into_dynamic()
+        .map_err(|e| anyhow::anyhow!("failed to convert atlas image: {:?}", e))?;
+
+    // The builder keeps insertion order, so frame names line up with the rects
+    let names = frames.into_iter().map(|(name, _)| name).collect();
+    Ok(PackedAtlas {
+        image,
+        layout: SerializableTextureAtlasLayout::new(&layout, names),
+    })
+}
 
-    (texture_atlas_layout, texture)
+fn collect_frames(
+    folder: &Path,
+    dir: &Path,
+    frames: &mut Vec<(String, PathBuf)>,
+) -> anyhow::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_frames(folder, &path, frames)?;
+        } else if path.extension().is_some_and(|extension| extension == "png") {
+            frames.push((frame_name(&path, folder), path));
+        }
+    }
+    Ok(())
 }
 
-// DEUBG ATLAS WITH SHOWING INDEX NUMBERS
-// let atlas_handle = texture_atlases.add(texture_atlas_linear.clone());
-
-// // Calculate grid dimensions
-// let sprites_count = texture_atlas_linear.textures.len();
-// let grid_size = (sprites_count as f32).sqrt().ceil() as usize;
-// let sprite_size = 32.0; // Adjust this based on your sprite size
-// let spacing = 10.0;
-
-// for (index, texture) in texture_atlas_linear.textures.iter().enumerate() {
-//     let row = index / grid_size;
-//     let col = index % grid_size;
-//     let x = col as f32 * (sprite_size + spacing)
-//         - (grid_size as f32 * (sprite_size + spacing)) / 2.0;
-//     let y = -(row as f32 * (sprite_size + spacing))
-//         + (grid_size as f32 * (sprite_size + spacing)) / 2.0;
-
-//     // Spawn sprite
-//     commands.spawn(SpriteSheetBundle {
-//         texture: linear_texture.clone(),
-//         atlas: TextureAtlas {
-//             index,
-//             layout: atlas_handle.clone(),
-//         },
-//         transform: Transform::from_xyz(x, y, 0.0),
-//         ..default()
-//     });
-
-//     // Spawn label
-//     create_label(
-//         &mut commands,
-//         (x, y - sprite_size / 2.0 - 5.0, 1.0),
-//         &index.to_string(),
-//         TextStyle {
-//             font_size: 8.0,
-//             color: Color::WHITE,
-//             ..default()
-//         },
-//     );
-// }
+/// `assets/enemies/undead/zombie/zombie_anim_f0.png` in folder `assets/enemies`
+/// becomes `undead/zombie/zombie_anim_f0`.
+fn frame_name(path: &Path, folder: &Path) -> String {
+    let relative = path.strip_prefix(folder).unwrap_or(path).with_extension("");
+    relative
+        .components()
+        .map(|component| component.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -1,11 +1,15 @@
 use bevy::prelude::*;
 
+use crate::animation::AnimationPlayer;
 use crate::audio::BackgroundMusic;
-use crate::player::{Health, Level, Player};
+use crate::enemy::{GameTime, NewLoopEvent, SpawnLoop};
+use crate::hero_registry::HeroRegistry;
+use crate::player::{Health, Level, MaxHealth, Player};
 use crate::resources::Score;
+use crate::save::SaveData;
 use crate::state::GameState;
-use crate::world::GameEntity;
-use crate::PLAYER_HEALTH;
+use crate::utils::format_time;
+use crate::world::{GameEntity, SelectedCharacter, DEFAULT_HERO};
 
 pub struct GuiPlugin;
 
@@ -14,6 +18,25 @@ struct DebugText;
 #[derive(Component)]
 struct MainMenuItem;
 
+#[derive(Component)]
+enum MainMenuButton {
+    Play,
+    PreviousHero,
+    NextHero,
+}
+
+#[derive(Component)]
+struct HeroPreview;
+
+#[derive(Component)]
+struct HeroNameText;
+
+#[derive(Component)]
+struct HeroStatsText;
+
+#[derive(Component)]
+struct LeaderboardText;
+
 #[derive(Component)]
 struct ScoreText;
 
@@ -26,6 +49,15 @@ struct HealthHeart;
 #[derive(Component)]
 struct LevelText;
 
+#[derive(Component)]
+struct RunTimerText;
+
+#[derive(Component)]
+struct LoopText;
+
+#[derive(Component)]
+struct LoopBanner(Timer);
+
 #[derive(Resource)]
 struct HeartAssets {
     full: Handle<Image>,
@@ -39,7 +71,12 @@ impl Plugin for GuiPlugin {
             .add_systems(OnEnter(GameState::GameInit), load_heart_assets)
             .add_systems(
                 Update,
-                handle_main_menu_buttons.run_if(in_state(GameState::MainMenu)),
+                (
+                    handle_main_menu_buttons,
+                    update_hero_carousel,
+                    update_leaderboard_text,
+                )
+                    .run_if(in_state(GameState::MainMenu)),
             )
             .add_systems(OnEnter(GameState::GameInit), spawn_debug_text)
             .add_systems(
@@ -54,6 +91,12 @@ impl Plugin for GuiPlugin {
             .add_systems(
                 Update,
                 update_experience_bar.run_if(in_state(GameState::InGame)),
+            )
+            .add_systems(OnEnter(GameState::GameInit), spawn_run_info)
+            .add_systems(
+                Update,
+                (update_run_info, spawn_loop_banner, update_loop_banner)
+                    .run_if(in_state(GameState::InGame)),
             );
     }
 }
@@ -91,7 +134,6 @@ fn spawn_debug_text(mut commands: Commands, asset_server: Res<AssetServer>) {
                         font: asset_server.load("monogram.ttf"),
                         font_size: 40.0,
                         color: Color::WHITE,
-                        ..default()
                     },
                 ),
                 ScoreText,
@@ -137,7 +179,6 @@ fn spawn_debug_text(mut commands: Commands, asset_server: Res<AssetServer>) {
                                 font: asset_server.load("monogram.ttf"),
                                 font_size: 40.0,
                                 color: Color::WHITE,
-                                ..default()
                             },
                         ),
                         DebugText,
@@ -154,7 +195,16 @@ fn update_score_text(mut query: Query<&mut Text, With<ScoreText>>, score: Res<Sc
     text.sections[0].value = format!("Score: {}", score.0);
 }
 
-fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
+fn setup_main_menu(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    hero_registry: Res<HeroRegistry>,
+) {
+    let preview_hero = hero_registry.get(DEFAULT_HERO).ok();
+    let preview_atlas = preview_hero
+        .map(|hero| hero.atlas.clone())
+        .unwrap_or_default();
+
     commands
         .spawn((
             NodeBundle {
@@ -196,21 +246,83 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                 ..default()
             });
 
-            // Play button
+            // Hero carousel
             parent
-                .spawn(ButtonBundle {
+                .spawn(NodeBundle {
                     style: Style {
-                        width: Val::Px(150.0),
-                        height: Val::Px(65.0),
-                        border: UiRect::all(Val::Px(5.0)),
-                        justify_content: JustifyContent::Center,
+                        flex_direction: FlexDirection::Row,
                         align_items: AlignItems::Center,
                         margin: UiRect::px(0.0, 0.0, 20.0, 0.0),
+                        column_gap: Val::Px(30.0),
                         ..default()
                     },
-                    border_color: BorderColor(Color::BLACK),
                     ..default()
                 })
+                .with_children(|parent| {
+                    spawn_carousel_button(parent, "<", MainMenuButton::PreviousHero);
+                    let mut preview = parent.spawn((
+                        AtlasImageBundle {
+                            style: Style {
+                                width: Val::Px(64.0),
+                                height: Val::Px(112.0),
+                                ..default()
+                            },
+                            texture_atlas: TextureAtlas {
+                                layout: preview_atlas.layout.unwrap_or_default(),
+                                index: 0,
+                            },
+                            image: UiImage::new(preview_atlas.image.unwrap_or_default()),
+                            ..default()
+                        },
+                        HeroPreview,
+                    ));
+                    if let Some(hero) = preview_hero {
+                        preview.insert(AnimationPlayer::new(hero.idle_clip().clone()));
+                    }
+                    spawn_carousel_button(parent, ">", MainMenuButton::NextHero);
+                });
+
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("monogram.ttf"),
+                        font_size: 48.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                HeroNameText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("monogram.ttf"),
+                        font_size: 28.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                HeroStatsText,
+            ));
+
+            // Play button
+            parent
+                .spawn((
+                    ButtonBundle {
+                        style: Style {
+                            width: Val::Px(150.0),
+                            height: Val::Px(65.0),
+                            border: UiRect::all(Val::Px(5.0)),
+                            justify_content: JustifyContent::Center,
+                            align_items: AlignItems::Center,
+                            margin: UiRect::px(0.0, 0.0, 20.0, 0.0),
+                            ..default()
+                        },
+                        border_color: BorderColor(Color::BLACK),
+                        ..default()
+                    },
+                    MainMenuButton::Play,
+                ))
                 .with_children(|parent| {
                     parent.spawn(TextBundle::from_section(
                         "Play",
@@ -222,26 +334,219 @@ fn setup_main_menu(mut commands: Commands, asset_server: Res<AssetServer>) {
                     ));
                 });
         });
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(20.0),
+                    right: Val::Px(20.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor::from(Color::BLACK.with_a(0.8)),
+                ..default()
+            },
+            MainMenuItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: asset_server.load("monogram.ttf"),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                LeaderboardText,
+            ));
+        });
+}
+
+fn spawn_carousel_button(parent: &mut ChildBuilder, label: &str, button: MainMenuButton) {
+    parent
+        .spawn((
+            ButtonBundle {
+                style: Style {
+                    width: Val::Px(65.0),
+                    height: Val::Px(65.0),
+                    border: UiRect::all(Val::Px(5.0)),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..default()
+                },
+                border_color: BorderColor(Color::BLACK),
+                ..default()
+            },
+            button,
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                label,
+                TextStyle {
+                    font_size: 40.0,
+                    color: Color::BLACK,
+                    ..default()
+                },
+            ));
+        });
 }
 
 fn handle_main_menu_buttons(
-    interaction_query: Query<&Interaction, (Changed<Interaction>, With<Button>)>,
+    interaction_query: Query<(&Interaction, &MainMenuButton), Changed<Interaction>>,
     mut next_state: ResMut<NextState<GameState>>,
     mut background_music: Query<&mut AudioSink, With<BackgroundMusic>>,
+    mut selected_character: ResMut<SelectedCharacter>,
+    hero_registry: Res<HeroRegistry>,
 ) {
-    for interaction in interaction_query.iter() {
-        match interaction {
-            Interaction::Pressed => {
-                if let Ok(mut sink) = background_music.get_single_mut() {
+    for (interaction, button) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        match button {
+            MainMenuButton::Play => {
+                if let Ok(sink) = background_music.get_single_mut() {
                     sink.play();
                 }
                 next_state.set(GameState::GameInit);
             }
-            _ => {}
+            MainMenuButton::PreviousHero => cycle_hero(&mut selected_character, &hero_registry, -1),
+            MainMenuButton::NextHero => cycle_hero(&mut selected_character, &hero_registry, 1),
         }
     }
 }
 
+fn cycle_hero(
+    selected_character: &mut SelectedCharacter,
+    hero_registry: &HeroRegistry,
+    step: isize,
+) {
+    let ids = hero_registry.ids();
+    if ids.is_empty() {
+        return;
+    }
+
+    let current = selected_character.0.as_deref().unwrap_or(DEFAULT_HERO);
+    let index = ids.iter().position(|id| id == current).unwrap_or(0) as isize;
+    let next = (index + step).rem_euclid(ids.len() as isize) as usize;
+    selected_character.0 = Some(ids[next].clone());
+}
+
+fn update_hero_carousel(
+    selected_character: Res<SelectedCharacter>,
+    hero_registry: Res<HeroRegistry>,
+    save_data: Res<SaveData>,
+    mut preview_query: Query<
+        (
+            &mut UiImage,
+            &mut TextureAtlas,
+            Option<&mut AnimationPlayer>,
+        ),
+        With<HeroPreview>,
+    >,
+    mut name_query: Query<&mut Text, (With<HeroNameText>, Without<HeroStatsText>)>,
+    mut stats_query: Query<&mut Text, (With<HeroStatsText>, Without<HeroNameText>)>,
+    added_preview_query: Query<(), Added<HeroPreview>>,
+) {
+    if !selected_character.is_changed() && !save_data.is_changed() && added_preview_query.is_empty()
+    {
+        return;
+    }
+
+    let id = selected_character.0.as_deref().unwrap_or(DEFAULT_HERO);
+    let Ok(hero) = hero_registry.get(id) else {
+        return;
+    };
+
+    if let Ok((mut image, mut texture_atlas, player)) = preview_query.get_single_mut() {
+        image.texture = hero.atlas.image.clone().unwrap_or_default();
+        texture_atlas.layout = hero.atlas.layout.clone().unwrap_or_default();
+        texture_atlas.index = hero.idle_clip().frames.first;
+        if let Some(mut player) = player {
+            *player = AnimationPlayer::new(hero.idle_clip().clone());
+        }
+    }
+    if let Ok(mut text) = name_query.get_single_mut() {
+        text.sections[0].value = hero.definition.display_name.clone();
+    }
+    if let Ok(mut text) = stats_query.get_single_mut() {
+        let stats = hero.definition.stats;
+        let health = stats.health + save_data.meta.bonuses().extra_health;
+        text.sections[0].value = format!("Health {}  Speed {}", health, stats.speed);
+    }
+}
+
+fn update_leaderboard_text(
+    selected_character: Res<SelectedCharacter>,
+    save_data: Res<SaveData>,
+    hero_registry: Res<HeroRegistry>,
+    mut leaderboard_query: Query<&mut Text, With<LeaderboardText>>,
+    added_leaderboard_query: Query<(), Added<LeaderboardText>>,
+) {
+    if !selected_character.is_changed() && added_leaderboard_query.is_empty() {
+        return;
+    }
+
+    let id = selected_character.0.as_deref().unwrap_or(DEFAULT_HERO);
+    let name = hero_registry
+        .get(id)
+        .map_or(id, |hero| hero.definition.display_name.as_str());
+    if let Ok(mut text) = leaderboard_query.get_single_mut() {
+        let mut lines = vec![format!("Best {} runs", name)];
+        lines.extend(high_score_lines(&save_data, id, None));
+        lines.push(String::new());
+        lines.push("Recent runs".to_string());
+        if save_data.history.is_empty() {
+            lines.push("  No runs yet".to_string());
+        }
+        for record in save_data.history.iter().take(5) {
+            lines.push(format!(
+                "  {:<10} {:>6}  {}",
+                record.hero,
+                record.score,
+                format_time(record.time_survived)
+            ));
+        }
+        text.sections[0].value = lines.join("\n");
+    }
+}
+
+/// Leaderboard lines for a hero, marking the run with `highlight_run` if present.
+pub fn high_score_lines(
+    save_data: &SaveData,
+    hero: &str,
+    highlight_run: Option<u32>,
+) -> Vec<String> {
+    let scores = save_data.high_scores(hero);
+    if scores.is_empty() {
+        return vec!["  No scores yet".to_string()];
+    }
+
+    scores
+        .iter()
+        .take(5)
+        .enumerate()
+        .map(|(rank, record)| {
+            let marker = if Some(record.run_number) == highlight_run {
+                " <"
+            } else {
+                ""
+            };
+            format!(
+                "  {}. {:>6}  {}  Lvl {}{}",
+                rank + 1,
+                record.score,
+                format_time(record.time_survived),
+                record.level,
+                marker
+            )
+        })
+        .collect()
+}
+
 fn despawn_main_menu(mut commands: Commands, menu_items_query: Query<Entity, With<MainMenuItem>>) {
     for e in menu_items_query.iter() {
         commands.entity(e).despawn_recursive();
@@ -332,13 +637,13 @@ fn spawn_health_bar(mut commands: Commands, asset_server: Res<AssetServer>) {
 }
 
 fn update_health_bar(
-    player_query: Query<&Health, With<Player>>,
+    player_query: Query<(&Health, &MaxHealth), With<Player>>,
     mut heart_query: Query<&mut UiImage, With<HealthHeart>>,
     heart_assets: Res<HeartAssets>,
 ) {
-    if let Ok(player_health) = player_query.get_single() {
+    if let Ok((player_health, max_health)) = player_query.get_single() {
         let total_hearts = heart_query.iter().count();
-        let health_percentage = player_health.0 as f32 / PLAYER_HEALTH as f32;
+        let health_percentage = player_health.0 / max_health.0;
 
         for (index, mut heart_image) in heart_query.iter_mut().enumerate() {
             let heart_threshold = (index + 1) as f32 / total_hearts as f32;
@@ -362,3 +667,138 @@ fn update_experience_bar(
         }
     }
 }
+
+fn spawn_run_info(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Start,
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::px(0.0, 0.0, 20.0, 0.0),
+                    ..default()
+                },
+                ..default()
+            },
+            GameEntity,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "00:00",
+                    TextStyle {
+                        font: asset_server.load("monogram.ttf"),
+                        font_size: 48.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                RunTimerText,
+            ));
+            parent.spawn((
+                TextBundle::from_section(
+                    "Loop 1",
+                    TextStyle {
+                        font: asset_server.load("monogram.ttf"),
+                        font_size: 24.0,
+                        color: Color::WHITE,
+                    },
+                ),
+                LoopText,
+            ));
+        });
+}
+
+fn update_run_info(
+    game_time: Option<Res<GameTime>>,
+    spawn_loop: Option<Res<SpawnLoop>>,
+    mut timer_query: Query<&mut Text, (With<RunTimerText>, Without<LoopText>)>,
+    mut loop_query: Query<&mut Text, (With<LoopText>, Without<RunTimerText>)>,
+) {
+    if let (Some(game_time), Ok(mut text)) = (game_time, timer_query.get_single_mut()) {
+        text.sections[0].value = format_time(game_time.0);
+    }
+    if let (Some(spawn_loop), Ok(mut text)) = (spawn_loop, loop_query.get_single_mut()) {
+        text.sections[0].value = format!("Loop {}", spawn_loop.0 + 1);
+    }
+}
+
+fn spawn_loop_banner(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut events: EventReader<NewLoopEvent>,
+    banner_query: Query<Entity, With<LoopBanner>>,
+) {
+    let Some(event) = events.read().last() else {
+        return;
+    };
+
+    for e in banner_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    width: Val::Percent(100.0),
+                    height: Val::Percent(100.0),
+                    position_type: PositionType::Absolute,
+                    align_items: AlignItems::Center,
+                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                ..default()
+            },
+            LoopBanner(Timer::from_seconds(3.0, TimerMode::Once)),
+            GameEntity,
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        padding: UiRect::all(Val::Px(16.0)),
+                        ..default()
+                    },
+                    background_color: BackgroundColor::from(Color::BLACK.with_a(0.7)),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    parent.spawn(TextBundle::from_section(
+                        format!("Loop {}", event.loop_index + 1),
+                        TextStyle {
+                            font: asset_server.load("monogram.ttf"),
+                            font_size: 64.0,
+                            color: Color::ORANGE_RED,
+                        },
+                    ));
+                    parent.spawn(TextBundle::from_section(
+                        format!("Enemies now have {}x health!", event.health_multiplier),
+                        TextStyle {
+                            font: asset_server.load("monogram.ttf"),
+                            font_size: 32.0,
+                            color: Color::WHITE,
+                        },
+                    ));
+                });
+        });
+}
+
+fn update_loop_banner(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut banner_query: Query<(Entity, &mut LoopBanner)>,
+) {
+    for (entity, mut banner) in banner_query.iter_mut() {
+        banner.0.tick(time.delta());
+        if banner.0.finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
@@ -0,0 +1,319 @@
+//! Runs the gameplay plugins under `MinimalPlugins`, with no window, renderer or
+//! audio, and a bot in place of the keyboard and mouse. Used by balance tests.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use crate::bridge::RequestedSeed;
+use crate::collision::CollisionPlugin;
+use crate::enemy::{
+    EnemiesData, EnemiesDataResource, Enemy, EnemyPlugin, EnemyType, GameTime, SpawnData,
+    SpawnDataResource,
+};
+use crate::player::{Health, Level, Player, PlayerInput, PlayerPlugin};
+use crate::resources::{RunStats, Score};
+use crate::simulation::{SimSet, SimulationPlugin, SIMULATION_HZ};
+use crate::spatial_index::SpatialIndexPlugin;
+use crate::state::GameState;
+use crate::wand::WandPlugin;
+use crate::world::WorldPlugin;
+
+/// What a bot can see on a given tick.
+pub struct BotView<'a> {
+    pub tick: u64,
+    pub player_position: Vec2,
+    pub enemy_positions: &'a [Vec2],
+}
+
+/// Decides the player input for each simulation tick.
+pub trait BotPolicy: Send + Sync + 'static {
+    fn decide(&mut self, view: &BotView) -> PlayerInput;
+}
+
+/// Never moves or casts.
+pub struct IdleBot;
+
+/// Stands still and casts at the nearest enemy.
+pub struct TurretBot;
+
+/// Casts at the nearest enemy while walking away from it.
+pub struct KitingBot;
+
+#[derive(Resource)]
+struct Bot(Box<dyn BotPolicy>);
+
+#[derive(Resource, Default)]
+struct TickCount(u64);
+
+/// The simulation plugins plus a bot driving `PlayerInput`.
+pub struct HeadlessPlugin;
+
+impl Plugin for HeadlessPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_state::<GameState>()
+            .init_resource::<TickCount>()
+            .add_plugins((
+                SimulationPlugin,
+                PlayerPlugin,
+                EnemyPlugin,
+                CollisionPlugin,
+                SpatialIndexPlugin,
+                WandPlugin,
+                WorldPlugin,
+            ))
+            .add_systems(FixedUpdate, drive_bot.in_set(SimSet::Input));
+    }
+}
+
+/// Summary of a headless run, taken when the player died or the time limit was hit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunSummary {
+    pub ticks: u64,
+    pub time_survived: f32,
+    pub died: bool,
+    pub score: u32,
+    pub level: u32,
+    pub kills: BTreeMap<String, u32>,
+}
+
+/// Snapshot of the battlefield, used to build difficulty curves.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FieldStats {
+    pub enemies_alive: u32,
+    pub enemy_health: f32,
+    pub damage_dealt: f32,
+    pub experience_earned: f32,
+    pub enemies_leashed: u32,
+}
+
+/// A single headless run, stepped one simulation tick per `App::update` unless
+/// sped up with [`HeadlessRun::set_speed`].
+pub struct HeadlessRun {
+    app: App,
+}
+
+impl HeadlessRun {
+    /// Starts a run with data loaded from `assets_dir`.
+    pub fn new(assets_dir: &Path, seed: u64, bot: impl BotPolicy) -> anyhow::Result<Self> {
+        let (enemies_data, spawn_data) = load_enemy_data(assets_dir)?;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, HeadlessPlugin))
+            .insert_resource(RequestedSeed(Some(seed)))
+            .insert_resource(EnemiesDataResource(enemies_data))
+            .insert_resource(SpawnDataResource::new(spawn_data))
+            .insert_resource(Bot(Box::new(bot)));
+        app.world
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::GameInit);
+        app.finish();
+        app.cleanup();
+
+        let mut run = Self { app };
+        run.set_speed(1);
+        // GameInit spawns the player, the next update enters InGame.
+        run.app.update();
+        run.app.update();
+        Ok(run)
+    }
+
+    pub fn world(&mut self) -> &mut World {
+        &mut self.app.world
+    }
+
+    pub fn state(&self) -> GameState {
+        *self.app.world.resource::<State<GameState>>().get()
+    }
+
+    /// Simulated seconds since the run started.
+    pub fn game_time(&self) -> f32 {
+        self.app
+            .world
+            .get_resource::<GameTime>()
+            .map_or(0.0, |t| t.0)
+    }
+
+    /// Runs `ticks_per_update` simulation ticks on every step. The outcome is the
+    /// same at any speed, only `run_for` may overshoot by up to one step.
+    pub fn set_speed(&mut self, ticks_per_update: u32) {
+        let step = Duration::from_secs_f64(ticks_per_update.max(1) as f64 / SIMULATION_HZ);
+        // Virtual time clamps each update to `max_delta`, 0.25s by default,
+        // which would silently cap the speed at 15 ticks per update.
+        self.app
+            .world
+            .resource_mut::<Time<Virtual>>()
+            .set_max_delta(step);
+        self.app
+            .insert_resource(TimeUpdateStrategy::ManualDuration(step));
+    }
+
+    pub fn step(&mut self) {
+        self.app.update();
+    }
+
+    /// Steps until the player dies or `max_seconds` of game time have passed.
+    pub fn run_for(&mut self, max_seconds: f32) -> RunSummary {
+        while self.state() == GameState::InGame && self.game_time() < max_seconds {
+            self.step();
+        }
+        self.summary()
+    }
+
+    pub fn summary(&mut self) -> RunSummary {
+        let died = self.state() == GameState::GameOver;
+        let run_stats = self.app.world.resource::<RunStats>().clone();
+        let level = self
+            .app
+            .world
+            .query_filtered::<&Level, With<Player>>()
+            .get_single(&self.app.world)
+            .map_or(run_stats.level_reached, |level| level.0);
+
+        RunSummary {
+            ticks: self.app.world.resource::<TickCount>().0,
+            time_survived: if died {
+                run_stats.time_survived
+            } else {
+                self.game_time()
+            },
+            died,
+            score: self.app.world.resource::<Score>().0,
+            level,
+            kills: run_stats.kills,
+        }
+    }
+
+    /// Number of living enemies per race id.
+    pub fn enemy_counts(&mut self) -> BTreeMap<String, u32> {
+        let mut counts = BTreeMap::new();
+        let mut query = self.app.world.query::<&EnemyType>();
+        for enemy_type in query.iter(&self.app.world) {
+            *counts.entry(enemy_type.race.clone()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    pub fn field_stats(&mut self) -> FieldStats {
+        let run_stats = self.app.world.resource::<RunStats>();
+        let mut stats = FieldStats {
+            damage_dealt: run_stats.damage_dealt,
+            experience_earned: run_stats.experience_earned,
+            enemies_leashed: run_stats.enemies_leashed,
+            ..default()
+        };
+
+        let mut query = self.app.world.query::<&Enemy>();
+        for enemy in query.iter(&self.app.world) {
+            stats.enemies_alive += 1;
+            stats.enemy_health += enemy.current_health.max(0.0);
+        }
+        stats
+    }
+
+    /// Keeps the player alive regardless of damage, for tests that only look at spawns.
+    pub fn make_player_invulnerable(&mut self) {
+        let mut query = self.app.world.query_filtered::<&mut Health, With<Player>>();
+        for mut health in query.iter_mut(&mut self.app.world) {
+            health.0 = f32::MAX;
+        }
+    }
+}
+
+/// Reads `enemies.json` and `enemy_spawns.json` from an assets directory.
+pub fn load_enemy_data(assets_dir: &Path) -> anyhow::Result<(EnemiesData, SpawnData)> {
+    let enemies_json = fs::read_to_string(assets_dir.join("enemies.json"))?;
+    let spawn_json = fs::read_to_string(assets_dir.join("enemy_spawns.json"))?;
+    let enemies_data: EnemiesData = serde_json::from_str(&enemies_json)?;
+    let spawn_data: SpawnData = serde_json::from_str(&spawn_json)?;
+    if let Err(errors) = enemies_data.validate(&spawn_data) {
+        let errors: Vec<String> = errors.iter().map(ToString::to_string).collect();
+        anyhow::bail!("invalid enemy_spawns.json: {}", errors.join(", "));
+    }
+    Ok((enemies_data, spawn_data))
+}
+
+fn drive_bot(
+    mut bot: ResMut<Bot>,
+    mut tick_count: ResMut<TickCount>,
+    mut input: ResMut<PlayerInput>,
+    player_query: Query<&Transform, With<Player>>,
+    enemy_query: Query<&Transform, With<Enemy>>,
+) {
+    let Ok(player_transform) = player_query.get_single() else {
+        return;
+    };
+
+    let enemy_positions: Vec<Vec2> = enemy_query
+        .iter()
+        .map(|transform| transform.translation.truncate())
+        .collect();
+    *input = bot.0.decide(&BotView {
+        tick: tick_count.0,
+        player_position: player_transform.translation.truncate(),
+        enemy_positions: &enemy_positions,
+    });
+    tick_count.0 += 1;
+}
+
+/// Looks up one of the built-in bots by name.
+pub fn bot_by_name(name: &str) -> Option<Box<dyn BotPolicy>> {
+    match name {
+        "idle" => Some(Box::new(IdleBot)),
+        "turret" => Some(Box::new(TurretBot)),
+        "kiting" => Some(Box::new(KitingBot)),
+        _ => None,
+    }
+}
+
+impl<B: BotPolicy + ?Sized> BotPolicy for Box<B> {
+    fn decide(&mut self, view: &BotView) -> PlayerInput {
+        (**self).decide(view)
+    }
+}
+
+impl BotView<'_> {
+    pub fn nearest_enemy(&self) -> Option<Vec2> {
+        self.enemy_positions.iter().copied().min_by(|a, b| {
+            a.distance_squared(self.player_position)
+                .total_cmp(&b.distance_squared(self.player_position))
+        })
+    }
+}
+
+impl BotPolicy for IdleBot {
+    fn decide(&mut self, _view: &BotView) -> PlayerInput {
+        PlayerInput::default()
+    }
+}
+
+impl BotPolicy for TurretBot {
+    fn decide(&mut self, view: &BotView) -> PlayerInput {
+        let aim = view.nearest_enemy();
+        PlayerInput {
+            movement: Vec2::ZERO,
+            aim,
+            cast: aim.is_some(),
+        }
+    }
+}
+
+impl BotPolicy for KitingBot {
+    fn decide(&mut self, view: &BotView) -> PlayerInput {
+        let Some(nearest) = view.nearest_enemy() else {
+            return PlayerInput::default();
+        };
+
+        let away = (view.player_position - nearest).normalize_or_zero();
+        PlayerInput {
+            // Inputs are key presses, so snap to the eight directions.
+            movement: away.round(),
+            aim: Some(nearest),
+            cast: true,
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::animation::{AnimationClip, AnimationClips, ClipDefinition};
+use crate::generate_texture_atlas::SerializableTextureAtlasLayout;
+use crate::resources::GlobalTextureAtlas;
+use crate::state::GameState;
+use crate::{PLAYER_HEALTH, PLAYER_SPEED};
+
+#[derive(Deserialize, Asset, TypePath, Clone)]
+pub struct HeroManifest {
+    pub heroes: Vec<HeroDefinition>,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct HeroDefinition {
+    pub id: String,
+    pub display_name: String,
+    pub stats: HeroStats,
+    /// Clips by player state, e.g. `idle` and `run`, in `hero_texture_atlas_layout.json`.
+    pub clips: BTreeMap<String, ClipDefinition>,
+}
+
+/// What a hero starts a run with, before meta upgrades.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct HeroStats {
+    pub health: f32,
+    /// Units per second.
+    pub speed: f32,
+}
+
+#[derive(Resource)]
+pub struct HeroManifestHandle(pub Handle<HeroManifest>);
+
+#[derive(Resource)]
+pub struct HeroTextureAtlasHandle(pub Handle<SerializableTextureAtlasLayout>);
+
+#[derive(Clone)]
+pub struct Hero {
+    pub definition: HeroDefinition,
+    /// The atlas shared by every hero.
+    pub atlas: GlobalTextureAtlas,
+    pub clips: AnimationClips,
+}
+
+/// Clips every hero needs, one per `PlayerState`.
+pub const REQUIRED_HERO_CLIPS: [&str; 4] = ["idle", "run", "hurt", "death"];
+
+/// All playable heroes keyed by id, in manifest order.
+#[derive(Resource, Default)]
+pub struct HeroRegistry {
+    heroes: HashMap<String, Hero>,
+    order: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnknownHero(pub String);
+
+pub struct HeroRegistryPlugin;
+
+impl Plugin for HeroRegistryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<HeroRegistry>()
+            .add_systems(Startup, load_json_resources)
+            .add_systems(OnExit(GameState::Loading), build_hero_registry);
+    }
+}
+
+fn load_json_resources(mut commands: Commands, asset_server: Res<AssetServer>) {
+    commands.insert_resource(HeroManifestHandle(asset_server.load("heroes.json")));
+    commands.insert_resource(HeroTextureAtlasHandle(
+        asset_server.load("hero_texture_atlas_layout.json"),
+    ));
+}
+
+pub fn build_hero_registry(
+    asset_server: Res<AssetServer>,
+    mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
+    manifest_handle: Res<HeroManifestHandle>,
+    manifests: Res<Assets<HeroManifest>>,
+    hero_texture_atlas_handle: Res<HeroTextureAtlasHandle>,
+    serializable_layouts: Res<Assets<SerializableTextureAtlasLayout>>,
+    mut registry: ResMut<HeroRegistry>,
+) {
+    let Some(manifest) = manifests.get(&manifest_handle.0) else {
+        error!("Hero manifest is not loaded, no heroes are available");
+        return;
+    };
+    let Some(layout) = serializable_layouts.get(&hero_texture_atlas_handle.0) else {
+        error!("Hero texture atlas is not loaded, no heroes are available");
+        return;
+    };
+
+    let mut texture_atlas_layout = TextureAtlasLayout::new_empty(layout.size);
+    for rect in layout.textures.clone() {
+        texture_atlas_layout.add_texture(rect);
+    }
+    let atlas = GlobalTextureAtlas {
+        image: Some(asset_server.load("hero_textures.png")),
+        layout: Some(texture_atlas_layouts.add(texture_atlas_layout)),
+    };
+
+    *registry = HeroRegistry::default();
+    for definition in manifest.heroes.iter() {
+        if registry.contains(&definition.id) {
+            warn!("Duplicate hero '{}' in manifest, skipping", definition.id);
+            continue;
+        }
+
+        let mut clips = AnimationClips::default();
+        for (name, clip) in definition.clips.iter() {
+            match clip.resolve(layout) {
+                Ok(clip) => {
+                    clips.0.insert(name.clone(), clip);
+                }
+                Err(e) => warn!("Hero '{}' {} clip: {}", definition.id, name, e),
+            }
+        }
+        if let Some(missing) = REQUIRED_HERO_CLIPS
+            .iter()
+            .find(|name| !clips.0.contains_key(**name))
+        {
+            warn!("Hero '{}' has no {} clip, skipping", definition.id, missing);
+            continue;
+        }
+
+        registry.insert(Hero {
+            definition: definition.clone(),
+            atlas: atlas.clone(),
+            clips,
+        });
+    }
+}
+
+impl Default for HeroStats {
+    /// Stats of runs without a hero registry, like headless ones.
+    fn default() -> Self {
+        Self {
+            health: PLAYER_HEALTH,
+            speed: PLAYER_SPEED,
+        }
+    }
+}
+
+impl Hero {
+    pub fn idle_clip(&self) -> &AnimationClip {
+        &self.clips.0["idle"]
+    }
+}
+
+impl HeroRegistry {
+    pub fn insert(&mut self, hero: Hero) {
+        let id = hero.definition.id.clone();
+        if self.heroes.insert(id.clone(), hero).is_none() {
+            self.order.push(id);
+        }
+    }
+
+    pub fn get(&self, id: &str) -> Result<&Hero, UnknownHero> {
+        self.heroes
+            .get(id)
+            .ok_or_else(|| UnknownHero(id.to_string()))
+    }
+
+    pub fn contains(&self, id: &str) -> bool {
+        self.heroes.contains_key(id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heroes.is_empty()
+    }
+
+    /// Hero ids in manifest order.
+    pub fn ids(&self) -> &[String] {
+        &self.order
+    }
+}
+
+impl fmt::Display for UnknownHero {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown hero '{}'", self.0)
+    }
+}
+
+impl std::error::Error for UnknownHero {}
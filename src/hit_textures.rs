@@ -1,13 +1,18 @@
+use crate::animation::{AnimationClip, ClipDefinition, PlayMode};
 use crate::generate_texture_atlas::SerializableTextureAtlasLayout;
 use crate::state::GameState;
 use bevy::prelude::*;
 
-#[derive(Resource)]
+#[derive(Resource, Default)]
 pub struct HitTextureAtlas {
     pub layout: Option<Handle<TextureAtlasLayout>>,
     pub image: Option<Handle<Image>>,
+    pub lightning_clip: Option<AnimationClip>,
 }
 
+pub const LIGHTNING_HIT_CLIP: &str = "lightning";
+const LIGHTNING_HIT_FPS: f32 = 16.0;
+
 #[derive(Resource)]
 pub struct HitTextureAtlasHandle(pub Handle<SerializableTextureAtlasLayout>);
 
@@ -57,14 +62,15 @@ fn setup_texture_atlas_from_files(
         // Update the HitTextureAtlas resource
         hit_texture_atlas.image = Some(texture_handle);
         hit_texture_atlas.layout = Some(layout_handle);
-    }
-}
 
-impl Default for HitTextureAtlas {
-    fn default() -> Self {
-        Self {
-            layout: None,
-            image: None,
+        let lightning_clip = ClipDefinition {
+            frames: LIGHTNING_HIT_CLIP.to_string(),
+            fps: LIGHTNING_HIT_FPS,
+            mode: PlayMode::Once,
+        };
+        match lightning_clip.resolve(layout) {
+            Ok(clip) => hit_texture_atlas.lightning_clip = Some(clip),
+            Err(e) => error!("Failed to load lightning hit sprite: {}", e),
         }
     }
 }
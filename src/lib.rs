@@ -1,18 +1,29 @@
 pub mod animation;
 pub mod asset_loading;
 pub mod audio;
+pub mod bridge;
 pub mod camera;
 pub mod collision;
 pub mod configs;
 pub mod enemy;
 pub mod enemy_bundle;
 pub mod enemy_textures;
+pub mod game_over;
 pub mod generate_texture_atlas;
 pub mod gui;
+pub mod headless;
+pub mod hero_registry;
 pub mod hit_textures;
 pub mod lightning_hit_bundle;
+pub mod meta_progression;
+pub mod platform;
 pub mod player;
+pub mod replay;
 pub mod resources;
+pub mod save;
+pub mod simulation;
+pub mod spatial_index;
+pub mod spawn_timeline;
 pub mod state;
 pub mod upgrade_menu;
 pub mod utils;
@@ -0,0 +1,297 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::save::{write_save_data, SaveData};
+use crate::state::GameState;
+
+/// Currency and permanent upgrades that carry over between runs.
+#[derive(Serialize, Deserialize, Default, Debug, Clone, PartialEq)]
+pub struct MetaProgression {
+    pub currency: u32,
+    /// Purchased level per upgrade id.
+    pub upgrades: BTreeMap<String, u32>,
+}
+
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetaUpgrade {
+    StartingHealth,
+    ExperienceGain,
+    RerollCharges,
+}
+
+pub const META_UPGRADES: [MetaUpgrade; 3] = [
+    MetaUpgrade::StartingHealth,
+    MetaUpgrade::ExperienceGain,
+    MetaUpgrade::RerollCharges,
+];
+
+/// Bonuses applied to the player when a run starts.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct MetaBonuses {
+    pub extra_health: f32,
+    pub experience_multiplier: f32,
+    pub reroll_charges: u32,
+}
+
+pub struct MetaProgressionPlugin;
+
+#[derive(Component)]
+struct ShopItem;
+
+#[derive(Component)]
+struct CurrencyText;
+
+#[derive(Component)]
+struct ShopEntryText(MetaUpgrade);
+
+impl Plugin for MetaProgressionPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::MainMenu), setup_shop)
+            .add_systems(OnExit(GameState::MainMenu), despawn_shop)
+            .add_systems(
+                Update,
+                (handle_shop_buttons, update_shop_text)
+                    .chain()
+                    .run_if(in_state(GameState::MainMenu)),
+            );
+    }
+}
+
+/// Currency earned at the end of a run.
+pub fn currency_for_run(score: u32, time_survived: f32) -> u32 {
+    score / 10 + (time_survived / 10.0) as u32
+}
+
+fn setup_shop(mut commands: Commands, asset_server: Res<AssetServer>) {
+    let font = asset_server.load("monogram.ttf");
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(20.0),
+                    left: Val::Px(20.0),
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(8.0),
+                    padding: UiRect::all(Val::Px(12.0)),
+                    ..default()
+                },
+                background_color: BackgroundColor::from(Color::BLACK.with_a(0.8)),
+                ..default()
+            },
+            ShopItem,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(
+                    "",
+                    TextStyle {
+                        font: font.clone(),
+                        font_size: 32.0,
+                        color: Color::GOLD,
+                    },
+                ),
+                CurrencyText,
+            ));
+
+            for upgrade in META_UPGRADES {
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Row,
+                            align_items: AlignItems::Center,
+                            column_gap: Val::Px(10.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent
+                            .spawn((
+                                ButtonBundle {
+                                    style: Style {
+                                        width: Val::Px(70.0),
+                                        height: Val::Px(36.0),
+                                        border: UiRect::all(Val::Px(3.0)),
+                                        justify_content: JustifyContent::Center,
+                                        align_items: AlignItems::Center,
+                                        ..default()
+                                    },
+                                    border_color: BorderColor(Color::BLACK),
+                                    ..default()
+                                },
+                                upgrade,
+                            ))
+                            .with_children(|parent| {
+                                parent.spawn(TextBundle::from_section(
+                                    "Buy",
+                                    TextStyle {
+                                        font_size: 24.0,
+                                        color: Color::BLACK,
+                                        ..default()
+                                    },
+                                ));
+                            });
+                        parent.spawn((
+                            TextBundle::from_section(
+                                "",
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: 24.0,
+                                    color: Color::WHITE,
+                                },
+                            ),
+                            ShopEntryText(upgrade),
+                        ));
+                    });
+            }
+        });
+}
+
+fn handle_shop_buttons(
+    interaction_query: Query<(&Interaction, &MetaUpgrade), Changed<Interaction>>,
+    mut save_data: ResMut<SaveData>,
+) {
+    for (interaction, upgrade) in interaction_query.iter() {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        if save_data.meta.purchase(*upgrade) {
+            write_save_data(&save_data);
+        }
+    }
+}
+
+fn update_shop_text(
+    save_data: Res<SaveData>,
+    mut currency_query: Query<&mut Text, (With<CurrencyText>, Without<ShopEntryText>)>,
+    mut entry_query: Query<(&mut Text, &ShopEntryText), Without<CurrencyText>>,
+    added_query: Query<(), Added<CurrencyText>>,
+) {
+    if !save_data.is_changed() && added_query.is_empty() {
+        return;
+    }
+
+    let meta = &save_data.meta;
+    if let Ok(mut text) = currency_query.get_single_mut() {
+        text.sections[0].value = format!("Gold: {}", meta.currency);
+    }
+    for (mut text, entry) in entry_query.iter_mut() {
+        let upgrade = entry.0;
+        let level = meta.level(upgrade);
+        let cost = match upgrade.cost(level) {
+            Some(cost) => format!("{} gold", cost),
+            None => "max".to_string(),
+        };
+        text.sections[0].value = format!(
+            "{} {}/{} ({})",
+            upgrade.name(),
+            level,
+            upgrade.max_level(),
+            cost
+        );
+    }
+}
+
+fn despawn_shop(mut commands: Commands, shop_items_query: Query<Entity, With<ShopItem>>) {
+    for e in shop_items_query.iter() {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+impl MetaUpgrade {
+    pub fn from_id(id: &str) -> Option<Self> {
+        META_UPGRADES.into_iter().find(|upgrade| upgrade.id() == id)
+    }
+
+    pub fn id(&self) -> &'static str {
+        match self {
+            MetaUpgrade::StartingHealth => "starting_health",
+            MetaUpgrade::ExperienceGain => "experience_gain",
+            MetaUpgrade::RerollCharges => "reroll_charges",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            MetaUpgrade::StartingHealth => "Vitality",
+            MetaUpgrade::ExperienceGain => "Wisdom",
+            MetaUpgrade::RerollCharges => "Fortune",
+        }
+    }
+
+    pub fn max_level(&self) -> u32 {
+        match self {
+            MetaUpgrade::StartingHealth => 5,
+            MetaUpgrade::ExperienceGain => 5,
+            MetaUpgrade::RerollCharges => 3,
+        }
+    }
+
+    /// Cost of buying the next level, or `None` if already maxed.
+    pub fn cost(&self, current_level: u32) -> Option<u32> {
+        if current_level >= self.max_level() {
+            return None;
+        }
+
+        let base = match self {
+            MetaUpgrade::StartingHealth => 50,
+            MetaUpgrade::ExperienceGain => 75,
+            MetaUpgrade::RerollCharges => 100,
+        };
+        Some(base * (current_level + 1))
+    }
+}
+
+impl Default for MetaBonuses {
+    /// No upgrades bought.
+    fn default() -> Self {
+        MetaProgression::default().bonuses()
+    }
+}
+
+impl MetaProgression {
+    pub fn level(&self, upgrade: MetaUpgrade) -> u32 {
+        self.upgrades
+            .get(upgrade.id())
+            .copied()
+            .unwrap_or(0)
+            .min(upgrade.max_level())
+    }
+
+    /// Level of every upgrade bought so far, by upgrade id.
+    pub fn levels(&self) -> BTreeMap<String, u32> {
+        META_UPGRADES
+            .into_iter()
+            .map(|upgrade| (upgrade.id().to_string(), self.level(upgrade)))
+            .filter(|(_, level)| *level > 0)
+            .collect()
+    }
+
+    /// Buys the next level of an upgrade. Returns false if it is maxed or unaffordable.
+    pub fn purchase(&mut self, upgrade: MetaUpgrade) -> bool {
+        let level = self.level(upgrade);
+        let Some(cost) = upgrade.cost(level) else {
+            return false;
+        };
+        if self.currency < cost {
+            return false;
+        }
+
+        self.currency -= cost;
+        self.upgrades.insert(upgrade.id().to_string(), level + 1);
+        true
+    }
+
+    pub fn bonuses(&self) -> MetaBonuses {
+        MetaBonuses {
+            extra_health: 10.0 * self.level(MetaUpgrade::StartingHealth) as f32,
+            experience_multiplier: 1.0 + 0.1 * self.level(MetaUpgrade::ExperienceGain) as f32,
+            reroll_charges: self.level(MetaUpgrade::RerollCharges),
+        }
+    }
+}
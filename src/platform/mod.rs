@@ -0,0 +1,12 @@
+//! Platform specific glue. The web backend talks to the host page through
+//! wasm-bindgen, the native backend uses CLI args and local files instead.
+
+#[cfg(not(target_arch = "wasm32"))]
+mod native;
+#[cfg(target_arch = "wasm32")]
+mod web;
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use native::*;
+#[cfg(target_arch = "wasm32")]
+pub use web::*;
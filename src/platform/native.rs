@@ -0,0 +1,137 @@
+use std::fs;
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::bridge::{
+    apply_pending_hero_selection, FileSink, HostBridge, RequestedSeed, StdoutSink,
+};
+use crate::hero_registry::{build_hero_registry, HeroRegistry};
+use crate::replay::{ActiveReplay, Replay};
+use crate::spatial_index::SpatialBackend;
+use crate::state::GameState;
+use crate::world::SelectedCharacter;
+
+pub struct PlatformPlugin;
+
+const SAVE_DIR: &str = "saves";
+
+/// Options passed on the command line, e.g. `--hero elf-f --seed 42 --results results.jsonl`
+/// or `--replay saves/last-replay.json`.
+#[derive(Debug, Default)]
+pub struct LaunchOptions {
+    pub hero: Option<String>,
+    pub seed: Option<u64>,
+    pub results_path: Option<PathBuf>,
+    pub replay_path: Option<PathBuf>,
+    pub spatial_backend: Option<SpatialBackend>,
+}
+
+/// Hero requested with `--hero`, selected once the hero registry is built.
+#[derive(Resource)]
+struct LaunchHero(String);
+
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        let options = LaunchOptions::from_args(std::env::args().skip(1));
+
+        if let Some(hero) = options.hero {
+            app.insert_resource(LaunchHero(hero));
+        }
+        if let Some(seed) = options.seed {
+            app.insert_resource(RequestedSeed(Some(seed)));
+        }
+        if let Some(spatial_backend) = options.spatial_backend {
+            app.insert_resource(spatial_backend);
+        }
+        if let Some(path) = options.replay_path {
+            match fs::read_to_string(&path)
+                .map_err(anyhow::Error::from)
+                .and_then(|contents| Replay::from_json(&contents))
+            {
+                Ok(replay) => {
+                    app.insert_resource(ActiveReplay::new(replay));
+                }
+                Err(e) => error!("Failed to load replay {}: {}", path.display(), e),
+            }
+        }
+
+        match options.results_path {
+            Some(path) => app.insert_resource(HostBridge::new(FileSink::new(path))),
+            None => app.insert_resource(HostBridge::new(StdoutSink)),
+        };
+
+        // A host command sent while loading wins over the command line.
+        app.add_systems(
+            OnExit(GameState::Loading),
+            check_launch_heroes
+                .after(build_hero_registry)
+                .before(apply_pending_hero_selection),
+        );
+    }
+}
+
+/// Applies `--hero` and checks the `--replay` hero, which can't be done while
+/// parsing the command line because the hero registry doesn't exist yet.
+fn check_launch_heroes(
+    mut commands: Commands,
+    launch_hero: Option<Res<LaunchHero>>,
+    active_replay: Option<Res<ActiveReplay>>,
+    hero_registry: Res<HeroRegistry>,
+    mut selected_character: ResMut<SelectedCharacter>,
+) {
+    if let Some(launch_hero) = launch_hero {
+        match hero_registry.get(&launch_hero.0) {
+            Ok(_) => selected_character.0 = Some(launch_hero.0.clone()),
+            Err(e) => error!("Ignoring --hero: {}", e),
+        }
+        commands.remove_resource::<LaunchHero>();
+    }
+    if let Some(active_replay) = active_replay {
+        if let Err(e) = hero_registry.get(&active_replay.replay.hero) {
+            error!("Cannot play the replay: {}", e);
+            commands.remove_resource::<ActiveReplay>();
+        }
+    }
+}
+
+impl LaunchOptions {
+    pub fn from_args(args: impl IntoIterator<Item = String>) -> Self {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--hero" => options.hero = args.next(),
+                "--seed" => match args.next().map(|seed| seed.parse()) {
+                    Some(Ok(seed)) => options.seed = Some(seed),
+                    _ => warn!("--seed expects a number"),
+                },
+                "--results" => options.results_path = args.next().map(PathBuf::from),
+                "--replay" => options.replay_path = args.next().map(PathBuf::from),
+                "--spatial-index" => {
+                    match args.next().as_deref().and_then(SpatialBackend::from_name) {
+                        Some(backend) => options.spatial_backend = Some(backend),
+                        None => warn!("--spatial-index expects kd-tree or hash"),
+                    }
+                }
+                _ => warn!("Ignoring unknown argument '{}'", arg),
+            }
+        }
+        options
+    }
+}
+
+/// Reads a value previously stored with [`write_storage`], from `saves/<key>.json`.
+pub fn read_storage(key: &str) -> Option<String> {
+    fs::read_to_string(storage_path(key)).ok()
+}
+
+pub fn write_storage(key: &str, value: &str) -> anyhow::Result<()> {
+    fs::create_dir_all(SAVE_DIR)?;
+    fs::write(storage_path(key), value)?;
+    Ok(())
+}
+
+fn storage_path(key: &str) -> PathBuf {
+    PathBuf::from(SAVE_DIR).join(format!("{}.json", key))
+}
@@ -0,0 +1,43 @@
+use bevy::prelude::*;
+use wasm_bindgen::prelude::*;
+
+use crate::bridge::{HostBridge, HostSink};
+
+pub struct PlatformPlugin;
+
+/// Forwards host messages to the page through `js-link.js`.
+struct JsSink;
+
+impl Plugin for PlatformPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HostBridge::new(JsSink));
+    }
+}
+
+/// Reads a value previously stored with [`write_storage`] from `localStorage`.
+pub fn read_storage(key: &str) -> Option<String> {
+    local_storage()?.get_item(key).ok().flatten()
+}
+
+pub fn write_storage(key: &str, value: &str) -> anyhow::Result<()> {
+    let storage = local_storage().ok_or_else(|| anyhow::anyhow!("localStorage unavailable"))?;
+    storage
+        .set_item(key, value)
+        .map_err(|e| anyhow::anyhow!("Failed to write localStorage: {:?}", e))
+}
+
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok().flatten()
+}
+
+impl HostSink for JsSink {
+    fn send(&self, payload: &str) -> anyhow::Result<()> {
+        send_to_js(payload.to_string());
+        Ok(())
+    }
+}
+
+#[wasm_bindgen(module = "/js-link.js")]
+extern "C" {
+    fn send_to_js(payload: String);
+}
@@ -1,15 +1,23 @@
 use bevy::math::vec3;
 use bevy::prelude::*;
-
+use serde::{Deserialize, Serialize};
+
+use crate::animation::AnimationState;
+use crate::collision::PlayerEnemyCollisionEvent;
+use crate::enemy::GameTime;
+use crate::replay::ActiveReplay;
+use crate::resources::update_cursor_position;
+use crate::simulation::SimSet;
 use crate::state::GameState;
 use crate::utils::{ease_in_out_quint, scale_value};
 use crate::*;
 
-use self::audio::{LevelUpEffectHandle, LevelUpSoundEffect};
-use bevy::audio::Volume;
-
 pub struct PlayerPlugin;
 
+/// Samples the keyboard and mouse into `PlayerInput`. Left out of headless runs,
+/// where a bot drives the player instead.
+pub struct PlayerInputPlugin;
+
 #[derive(Component)]
 pub struct Player;
 #[derive(Component)]
@@ -18,37 +26,94 @@ pub struct Health(pub f32);
 pub struct Experience(pub f32);
 #[derive(Component)]
 pub struct Level(pub u32);
+#[derive(Component)]
+pub struct MaxHealth(pub f32);
+/// Units per second the player walks.
+#[derive(Component)]
+pub struct MoveSpeed(pub f32);
+/// Multiplier applied to experience gained from kills.
+#[derive(Component)]
+pub struct ExperienceMultiplier(pub f32);
+/// Rerolls available in the upgrade menu this run, from the Fortune meta upgrade.
+#[derive(Component)]
+pub struct RerollCharges(pub u32);
 
-#[derive(Component, Default)]
+#[derive(Component, Debug, Clone, Copy, Default, PartialEq)]
 pub enum PlayerState {
     #[default]
     Idle,
     Run,
+    /// Touched by an enemy, for the given seconds.
+    Hurt(f32),
+    /// Out of health. The run ends once the given seconds have passed.
+    Dead(f32),
+}
+
+impl AnimationState for PlayerState {
+    fn clip_name(&self) -> &str {
+        match self {
+            PlayerState::Idle => "idle",
+            PlayerState::Run => "run",
+            PlayerState::Hurt(_) => "hurt",
+            PlayerState::Dead(_) => "death",
+        }
+    }
 }
 
 #[derive(Event)]
-pub struct PlayerEnemyCollisionEvent;
+pub struct LevelUpEvent {
+    pub level: u32,
+}
+
+/// Player input for the current simulation tick. Sampled from the keyboard and
+/// mouse, or fed from a replay.
+#[derive(Resource, Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct PlayerInput {
+    /// Unnormalized movement axes, each -1, 0 or 1.
+    #[serde(rename = "m")]
+    pub movement: Vec2,
+    /// World position the wand points at.
+    #[serde(rename = "a")]
+    pub aim: Option<Vec2>,
+    #[serde(rename = "c")]
+    pub cast: bool,
+}
 
 impl Plugin for PlayerPlugin {
     fn build(&self, app: &mut App) {
-        app.add_event::<PlayerEnemyCollisionEvent>().add_systems(
+        app.add_event::<LevelUpEvent>()
+            .init_resource::<PlayerInput>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    move_player.in_set(SimSet::Movement),
+                    (handle_player_enemy_collision_events, handle_player_level_up)
+                        .chain()
+                        .in_set(SimSet::Cleanup),
+                    handle_player_death
+                        .after(SimSet::Cleanup)
+                        .run_if(in_state(GameState::InGame)),
+                ),
+            );
+    }
+}
+
+impl Plugin for PlayerInputPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
             Update,
-            (
-                handle_player_death,
-                handle_player_input,
-                handle_player_enemy_collision_events,
-                handle_player_level_up,
-            )
-                .run_if(in_state(GameState::InGame)),
+            read_player_input
+                .after(update_cursor_position)
+                .run_if(in_state(GameState::InGame))
+                .run_if(not(resource_exists::<ActiveReplay>)),
         );
     }
 }
 
 fn handle_player_level_up(
-    mut commands: Commands,
     mut experience_query: Query<&mut Experience, With<Player>>,
     mut level_query: Query<&mut Level, With<Player>>,
-    level_up_sound: Res<LevelUpEffectHandle>,
+    mut level_up_events: EventWriter<LevelUpEvent>,
 ) {
     if experience_query.is_empty() || level_query.is_empty() {
         return;
@@ -59,18 +124,7 @@ fn handle_player_level_up(
     if experience.0 >= experience_per_level {
         experience.0 -= experience_per_level;
         level.0 += 1;
-
-        commands.spawn((
-            AudioBundle {
-                source: level_up_sound.handle.clone(),
-                settings: PlaybackSettings {
-                    volume: Volume::new(0.3),
-                    ..default()
-                },
-                ..default()
-            },
-            LevelUpSoundEffect,
-        ));
+        level_up_events.send(LevelUpEvent { level: level.0 });
     }
 }
 
@@ -83,46 +137,63 @@ fn experience_curve(level: u32) -> f32 {
     };
     let multiplier = scale_value(ease_in_out_quint(leveling_progress), 10.0);
     let exp_progress = leveling_progress * leveling_progress; // easeInQuad easing fn
-    let experience = (multiplier + exp_progress) * experience_per_level;
-    return experience;
+    (multiplier + exp_progress) * experience_per_level
 }
 
 fn handle_player_enemy_collision_events(
-    mut player_query: Query<&mut Health, With<Player>>,
+    mut player_query: Query<(&mut Health, &mut PlayerState), With<Player>>,
     mut events: EventReader<PlayerEnemyCollisionEvent>,
+    time: Res<Time>,
 ) {
     if player_query.is_empty() {
         return;
     }
 
-    let mut health = player_query.single_mut();
+    let (mut health, mut player_state) = player_query.single_mut();
     for _ in events.read() {
-        health.0 -= ENEMY_DAMAGE;
+        health.0 -= ENEMY_DAMAGE * time.delta_seconds();
+        *player_state = PlayerState::Hurt(PLAYER_HURT_DURATION);
     }
 }
 
+/// The simulation stops as soon as the player runs out of health, then the
+/// death clip gets `PLAYER_DEATH_DURATION` to play before the game is over.
 fn handle_player_death(
-    player_query: Query<&Health, With<Player>>,
+    mut player_query: Query<(&Health, &Level, &mut PlayerState), With<Player>>,
+    game_time: Option<Res<GameTime>>,
+    time: Res<Time>,
+    mut run_stats: ResMut<RunStats>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    if player_query.is_empty() {
+    let Ok((health, level, mut player_state)) = player_query.get_single_mut() else {
+        return;
+    };
+    if health.0 > 0.0 {
         return;
     }
-    let health = player_query.single();
-    if health.0 <= 0.0 {
-        next_state.set(GameState::MainMenu);
-    }
+
+    *player_state = match *player_state {
+        PlayerState::Dead(remaining) if remaining > time.delta_seconds() => {
+            PlayerState::Dead(remaining - time.delta_seconds())
+        }
+        PlayerState::Dead(_) => {
+            next_state.set(GameState::GameOver);
+            PlayerState::Dead(0.0)
+        }
+        _ => {
+            run_stats.level_reached = level.0;
+            run_stats.time_survived = game_time.map_or(0.0, |t| t.0);
+            PlayerState::Dead(PLAYER_DEATH_DURATION)
+        }
+    };
 }
 
-fn handle_player_input(
-    mut player_query: Query<(&mut Transform, &mut PlayerState), With<Player>>,
+fn read_player_input(
+    mut input: ResMut<PlayerInput>,
     keyboard_input: Res<ButtonInput<KeyCode>>,
+    mouse_button_input: Res<ButtonInput<MouseButton>>,
+    cursor_pos: Res<CursorPosition>,
 ) {
-    if player_query.is_empty() {
-        return;
-    }
-
-    let (mut transform, mut player_state) = player_query.single_mut();
     let w_key = keyboard_input.pressed(KeyCode::KeyW) || keyboard_input.pressed(KeyCode::ArrowUp);
     let a_key = keyboard_input.pressed(KeyCode::KeyA) || keyboard_input.pressed(KeyCode::ArrowLeft);
     let s_key = keyboard_input.pressed(KeyCode::KeyS) || keyboard_input.pressed(KeyCode::ArrowDown);
@@ -142,13 +213,36 @@ fn handle_player_input(
     if d_key {
         delta.x += 1.0;
     }
-    delta = delta.normalize();
 
-    if delta.is_finite() && (w_key || a_key || s_key || d_key) {
-        transform.translation += vec3(delta.x, delta.y, 0.0) * PLAYER_SPEED;
+    *input = PlayerInput {
+        movement: delta,
+        aim: cursor_pos.0,
+        cast: mouse_button_input.pressed(MouseButton::Left),
+    };
+}
+
+fn move_player(
+    mut player_query: Query<(&mut Transform, &mut PlayerState, &MoveSpeed), With<Player>>,
+    input: Res<PlayerInput>,
+    time: Res<Time>,
+) {
+    if player_query.is_empty() {
+        return;
+    }
+
+    let (mut transform, mut player_state, speed) = player_query.single_mut();
+    let delta = input.movement.normalize();
+    let moving = delta.is_finite() && input.movement != Vec2::ZERO;
+
+    if moving {
+        transform.translation += vec3(delta.x, delta.y, 0.0) * speed.0 * time.delta_seconds();
         transform.translation.z = 10.0;
-        *player_state = PlayerState::Run;
-    } else {
-        *player_state = PlayerState::Idle;
     }
+    *player_state = match *player_state {
+        PlayerState::Hurt(remaining) if remaining > time.delta_seconds() => {
+            PlayerState::Hurt(remaining - time.delta_seconds())
+        }
+        _ if moving => PlayerState::Run,
+        _ => PlayerState::Idle,
+    };
 }
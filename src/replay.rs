@@ -0,0 +1,269 @@
+use std::collections::BTreeMap;
+
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::bridge::{HostBridge, HostMessage, GAME_VERSION};
+use crate::meta_progression::MetaBonuses;
+use crate::platform::write_storage;
+use crate::player::PlayerInput;
+use crate::resources::{RunRng, Score};
+use crate::save::SaveData;
+use crate::simulation::SimSet;
+use crate::state::GameState;
+use crate::world::{init_world, RunHero, SelectedCharacter, DEFAULT_HERO};
+
+pub const REPLAY_KEY: &str = "last-replay";
+pub const REPLAY_FORMAT_VERSION: u32 = 1;
+
+/// Everything needed to reproduce a run: its starting conditions and the
+/// player input for every simulation tick, run-length encoded.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub format: u32,
+    pub game_version: String,
+    pub seed: u64,
+    pub hero: String,
+    pub bonuses: MetaBonuses,
+    /// Meta upgrade levels behind `bonuses`, by upgrade id. Only shown in the run summary.
+    #[serde(default)]
+    pub upgrades: BTreeMap<String, u32>,
+    pub inputs: Vec<InputRun>,
+    /// Final score of the recorded run, checked when the replay finishes.
+    pub score: Option<u32>,
+}
+
+/// The same input repeated for `ticks` simulation ticks.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct InputRun {
+    #[serde(rename = "n")]
+    pub ticks: u32,
+    #[serde(rename = "i")]
+    pub input: PlayerInput,
+}
+
+/// Input of the run being played, recorded on the fly.
+#[derive(Resource)]
+pub struct ReplayRecorder(pub Replay);
+
+/// Present while a replay is driving the player instead of live input.
+#[derive(Resource)]
+pub struct ActiveReplay {
+    pub replay: Replay,
+    run: usize,
+    tick_in_run: u32,
+}
+
+/// Starting conditions of the next run, taken from the replay when one is playing.
+#[derive(SystemParam)]
+pub struct RunSetup<'w> {
+    selected_character: Res<'w, SelectedCharacter>,
+    save_data: Option<Res<'w, SaveData>>,
+    active_replay: Option<Res<'w, ActiveReplay>>,
+}
+
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ReplayRecorder(Replay::new(
+            0,
+            DEFAULT_HERO,
+            default(),
+            default(),
+        )))
+        .add_systems(OnEnter(GameState::MainMenu), start_pending_replay)
+        .add_systems(
+            OnEnter(GameState::GameInit),
+            start_recording.after(init_world),
+        )
+        .add_systems(FixedUpdate, drive_player_input.in_set(SimSet::Input))
+        .add_systems(OnEnter(GameState::GameOver), finish_replay)
+        .add_systems(OnExit(GameState::GameOver), stop_replay);
+    }
+}
+
+/// A replay loaded at launch starts as soon as the main menu is reached.
+fn start_pending_replay(
+    active_replay: Option<Res<ActiveReplay>>,
+    mut next_state: ResMut<NextState<GameState>>,
+) {
+    if active_replay.is_some() {
+        next_state.set(GameState::GameInit);
+    }
+}
+
+fn start_recording(
+    mut recorder: ResMut<ReplayRecorder>,
+    run_rng: Res<RunRng>,
+    run_hero: Option<Res<RunHero>>,
+    run_setup: RunSetup,
+) {
+    let Some(run_hero) = run_hero else {
+        return;
+    };
+    recorder.0 = Replay::new(
+        run_rng.seed(),
+        &run_hero.0,
+        run_setup.bonuses(),
+        run_setup.upgrades(),
+    );
+}
+
+fn drive_player_input(
+    mut input: ResMut<PlayerInput>,
+    mut recorder: ResMut<ReplayRecorder>,
+    active_replay: Option<ResMut<ActiveReplay>>,
+) {
+    if let Some(mut active_replay) = active_replay {
+        *input = active_replay.next_input().unwrap_or_default();
+    }
+    recorder.0.push(*input);
+}
+
+fn finish_replay(
+    score: Res<Score>,
+    mut recorder: ResMut<ReplayRecorder>,
+    active_replay: Option<Res<ActiveReplay>>,
+    bridge: Option<Res<HostBridge>>,
+) {
+    recorder.0.score = Some(score.0);
+
+    if let Some(active_replay) = active_replay {
+        let expected_score = active_replay.replay.score;
+        let matches = expected_score == Some(score.0);
+        if matches {
+            info!("Replay finished with the recorded score {}", score.0);
+        } else {
+            warn!(
+                "Replay diverged: recorded score {:?}, replayed score {}",
+                expected_score, score.0
+            );
+        }
+        if let Some(bridge) = bridge {
+            bridge.send(&HostMessage::ReplayCheck {
+                expected_score,
+                score: score.0,
+                matches,
+            });
+        }
+        return;
+    }
+
+    info!("Recorded replay of {} ticks", recorder.0.ticks());
+    match serde_json::to_string(&recorder.0) {
+        Ok(json) => {
+            if let Err(e) = write_storage(REPLAY_KEY, &json) {
+                warn!("Failed to save replay: {}", e);
+            }
+        }
+        Err(e) => warn!("Failed to serialize replay: {}", e),
+    }
+    if let Some(bridge) = bridge {
+        bridge.send(&HostMessage::Replay(recorder.0.clone()));
+    }
+}
+
+fn stop_replay(mut commands: Commands) {
+    commands.remove_resource::<ActiveReplay>();
+}
+
+impl Replay {
+    pub fn new(
+        seed: u64,
+        hero: &str,
+        bonuses: MetaBonuses,
+        upgrades: BTreeMap<String, u32>,
+    ) -> Self {
+        Self {
+            format: REPLAY_FORMAT_VERSION,
+            game_version: GAME_VERSION.to_string(),
+            seed,
+            hero: hero.to_string(),
+            bonuses,
+            upgrades,
+            inputs: Vec::new(),
+            score: None,
+        }
+    }
+
+    pub fn from_json(contents: &str) -> anyhow::Result<Self> {
+        let replay: Replay = serde_json::from_str(contents)?;
+        if replay.format != REPLAY_FORMAT_VERSION {
+            anyhow::bail!("unsupported replay format {}", replay.format);
+        }
+        if replay.game_version != GAME_VERSION {
+            warn!(
+                "Replay was recorded with version {}, running {}. It may not play back identically",
+                replay.game_version, GAME_VERSION
+            );
+        }
+        Ok(replay)
+    }
+
+    pub fn push(&mut self, input: PlayerInput) {
+        match self.inputs.last_mut() {
+            Some(last) if last.input == input => last.ticks += 1,
+            _ => self.inputs.push(InputRun { ticks: 1, input }),
+        }
+    }
+
+    pub fn ticks(&self) -> u64 {
+        self.inputs.iter().map(|run| run.ticks as u64).sum()
+    }
+}
+
+impl RunSetup<'_> {
+    pub fn hero(&self) -> &str {
+        match &self.active_replay {
+            Some(active_replay) => &active_replay.replay.hero,
+            None => self.selected_character.0.as_deref().unwrap_or(DEFAULT_HERO),
+        }
+    }
+
+    pub fn bonuses(&self) -> MetaBonuses {
+        match &self.active_replay {
+            Some(active_replay) => active_replay.replay.bonuses,
+            None => self
+                .save_data
+                .as_ref()
+                .map_or_else(MetaBonuses::default, |save_data| save_data.meta.bonuses()),
+        }
+    }
+
+    /// Meta upgrade levels by upgrade id, matching [`RunSetup::bonuses`].
+    pub fn upgrades(&self) -> BTreeMap<String, u32> {
+        match &self.active_replay {
+            Some(active_replay) => active_replay.replay.upgrades.clone(),
+            None => self
+                .save_data
+                .as_ref()
+                .map(|save_data| save_data.meta.levels())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl ActiveReplay {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            run: 0,
+            tick_in_run: 0,
+        }
+    }
+
+    /// Input for the next tick, or `None` once the recording has run out.
+    pub fn next_input(&mut self) -> Option<PlayerInput> {
+        while let Some(run) = self.replay.inputs.get(self.run) {
+            if self.tick_in_run < run.ticks {
+                self.tick_in_run += 1;
+                return Some(run.input);
+            }
+            self.run += 1;
+            self.tick_in_run = 0;
+        }
+        None
+    }
+}
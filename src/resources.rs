@@ -1,34 +1,53 @@
+use std::collections::BTreeMap;
+
 use bevy::prelude::*;
 use bevy::window::PrimaryWindow;
+use rand::{Rng, RngCore, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
+use crate::bridge::RequestedSeed;
+use crate::replay::ActiveReplay;
 use crate::state::GameState;
 use crate::*;
 
 pub struct ResourcesPlugin;
 
-#[derive(Resource, Clone)]
+#[derive(Resource, Clone, Default)]
 pub struct GlobalTextureAtlas {
     pub layout: Option<Handle<TextureAtlasLayout>>,
     pub image: Option<Handle<Image>>,
 }
 
 #[derive(Resource)]
-pub struct HeroTextureAtlases {
-    pub dwarf_f: GlobalTextureAtlas,
-    pub dwarf_m: GlobalTextureAtlas,
-    pub wizzard_f: GlobalTextureAtlas,
-    pub wizzard_m: GlobalTextureAtlas,
-    pub elf_f: GlobalTextureAtlas,
-    pub elf_m: GlobalTextureAtlas,
-    pub knight_f: GlobalTextureAtlas,
-    pub knight_m: GlobalTextureAtlas,
-    pub lizard_f: GlobalTextureAtlas,
-    pub lizard_m: GlobalTextureAtlas,
-    pub doc: GlobalTextureAtlas,
+pub struct Score(pub u32);
+
+/// Stats collected over a single run and shown on the game over screen.
+#[derive(Resource, Default, Debug, Clone)]
+pub struct RunStats {
+    pub time_survived: f32,
+    pub level_reached: u32,
+    pub kills: BTreeMap<String, u32>,
+    pub damage_dealt: f32,
+    pub experience_earned: f32,
+    /// Enemies moved back to the spawn ring for straying too far from the player.
+    pub enemies_leashed: u32,
+    /// Meta upgrade levels applied when the run started, by upgrade id.
+    pub upgrades_taken: BTreeMap<String, u32>,
+}
+
+impl RunStats {
+    pub fn total_kills(&self) -> u32 {
+        self.kills.values().sum()
+    }
 }
 
+/// Random number generator for everything that affects a run. Seeded at
+/// `GameInit` so the same seed reproduces the same spawns.
 #[derive(Resource)]
-pub struct Score(pub u32);
+pub struct RunRng {
+    seed: u64,
+    rng: ChaCha8Rng,
+}
 
 #[derive(Resource)]
 pub struct CursorPosition(pub Option<Vec2>);
@@ -36,8 +55,6 @@ pub struct CursorPosition(pub Option<Vec2>);
 impl Plugin for ResourcesPlugin {
     fn build(&self, app: &mut App) {
         app.insert_resource(GlobalTextureAtlas::default())
-            .insert_resource(HeroTextureAtlases::default())
-            .insert_resource(Score(0))
             .insert_resource(CursorPosition(None))
             .add_systems(OnEnter(GameState::Loading), load_assets)
             .add_systems(
@@ -49,7 +66,6 @@ impl Plugin for ResourcesPlugin {
 
 fn load_assets(
     mut handle: ResMut<GlobalTextureAtlas>,
-    mut hero_atlases_handle: ResMut<HeroTextureAtlases>,
     asset_server: Res<AssetServer>,
     mut texture_atlas_layouts: ResMut<Assets<TextureAtlasLayout>>,
 ) {
@@ -63,57 +79,60 @@ fn load_assets(
         None,
     );
     handle.layout = Some(texture_atlas_layouts.add(layout));
+}
+
+pub fn reset_run_resources(mut score: ResMut<Score>, mut run_stats: ResMut<RunStats>) {
+    score.0 = 0;
+    *run_stats = RunStats::default();
+}
+
+/// Seeds the run from the replay being played or the requested seed, or a
+/// random one if neither is set.
+pub fn seed_run_rng(
+    mut run_rng: ResMut<RunRng>,
+    requested_seed: Res<RequestedSeed>,
+    active_replay: Option<Res<ActiveReplay>>,
+) {
+    let seed = active_replay
+        .map(|active_replay| active_replay.replay.seed)
+        .or(requested_seed.0)
+        .unwrap_or_else(|| rand::thread_rng().gen());
+    info!("Run seed: {}", seed);
+    *run_rng = RunRng::new(seed);
+}
+
+impl RunRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
 
-    let hero_layout =
-        TextureAtlasLayout::from_grid(Vec2::new(16 as f32, 28 as f32), 4, 2, None, None);
-
-    hero_atlases_handle.doc = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/doc.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.dwarf_f = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/dwarf-f.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.dwarf_m = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/dwarf-m.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.elf_f = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/elf-f.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.elf_m = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/elf-m.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.knight_f = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/knight-f.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.knight_m = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/knight-m.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.lizard_f = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/lizard-f.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.lizard_m = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/lizard-m.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.wizzard_f = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/wizzard-f.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
-    hero_atlases_handle.wizzard_m = GlobalTextureAtlas {
-        image: Some(asset_server.load("heroes/wizzard-m.png")),
-        layout: Some(texture_atlas_layouts.add(hero_layout.clone())),
-    };
+impl RngCore for RunRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
 }
 
-fn update_cursor_position(
+pub fn update_cursor_position(
     mut cursor_pos: ResMut<CursorPosition>,
     window_query: Query<&Window, With<PrimaryWindow>>,
     camera_query: Query<(&Camera, &GlobalTransform), With<Camera>>,
@@ -129,49 +148,3 @@ fn update_cursor_position(
         .and_then(|cursor| camera.viewport_to_world(camera_transform, cursor))
         .map(|ray| ray.origin.truncate());
 }
-
-impl Default for GlobalTextureAtlas {
-    fn default() -> Self {
-        Self {
-            layout: None,
-            image: None,
-        }
-    }
-}
-
-impl Default for HeroTextureAtlases {
-    fn default() -> Self {
-        Self {
-            doc: GlobalTextureAtlas::default(),
-            dwarf_f: GlobalTextureAtlas::default(),
-            dwarf_m: GlobalTextureAtlas::default(),
-            wizzard_f: GlobalTextureAtlas::default(),
-            wizzard_m: GlobalTextureAtlas::default(),
-            elf_f: GlobalTextureAtlas::default(),
-            elf_m: GlobalTextureAtlas::default(),
-            knight_f: GlobalTextureAtlas::default(),
-            knight_m: GlobalTextureAtlas::default(),
-            lizard_f: GlobalTextureAtlas::default(),
-            lizard_m: GlobalTextureAtlas::default(),
-        }
-    }
-}
-
-impl HeroTextureAtlases {
-    pub fn get_hero(&self, hero: &str) -> Option<GlobalTextureAtlas> {
-        match hero {
-            "doc" => Some(self.doc.clone()),
-            "dwarf-f" => Some(self.dwarf_f.clone()),
-            "dwarf-m" => Some(self.dwarf_m.clone()),
-            "wizzard-f" => Some(self.wizzard_f.clone()),
-            "wizzard-m" => Some(self.wizzard_m.clone()),
-            "elf-f" => Some(self.elf_f.clone()),
-            "elf-m" => Some(self.elf_m.clone()),
-            "knight-f" => Some(self.knight_f.clone()),
-            "knight-m" => Some(self.knight_m.clone()),
-            "lizard-f" => Some(self.lizard_f.clone()),
-            "lizard-m" => Some(self.lizard_m.clone()),
-            _ => None,
-        }
-    }
-}
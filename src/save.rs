@@ -0,0 +1,220 @@
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, VecDeque};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::meta_progression::{currency_for_run, MetaProgression};
+use crate::platform::{read_storage, write_storage};
+use crate::replay::ActiveReplay;
+use crate::resources::{RunStats, Score};
+use crate::state::GameState;
+use crate::world::SelectedCharacter;
+
+pub const SAVE_KEY: &str = "eternal-gauntlet-save";
+/// Where a stored save that failed to load is copied before it can be replaced.
+pub const SAVE_BACKUP_KEY: &str = "eternal-gauntlet-save-backup";
+pub const SAVE_VERSION: u32 = 2;
+pub const MAX_HIGH_SCORES_PER_HERO: usize = 10;
+pub const MAX_RUN_HISTORY: usize = 20;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RunRecord {
+    pub run_number: u32,
+    pub hero: String,
+    pub score: u32,
+    pub time_survived: f32,
+    pub level: u32,
+    pub kills: u32,
+    #[serde(default)]
+    pub currency_earned: u32,
+}
+
+/// Everything persisted between sessions. Stored as JSON with a `version`
+/// field so older saves can be migrated on load.
+#[derive(Resource, Serialize, Deserialize, Debug, Clone)]
+pub struct SaveData {
+    pub version: u32,
+    pub runs_played: u32,
+    pub high_scores: BTreeMap<String, Vec<RunRecord>>,
+    pub history: VecDeque<RunRecord>,
+    pub meta: MetaProgression,
+    /// Set when the stored save could not be loaded and must not be written
+    /// over, e.g. one from a newer build.
+    #[serde(skip)]
+    pub read_only: bool,
+}
+
+/// The record of the run that just ended, if it was saved.
+#[derive(Resource, Default, Debug)]
+pub struct LastRun(pub Option<RunRecord>);
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(load_save_data())
+            .init_resource::<LastRun>()
+            .add_systems(OnEnter(GameState::GameOver), record_run);
+    }
+}
+
+fn load_save_data() -> SaveData {
+    let Some(contents) = read_storage(SAVE_KEY) else {
+        return SaveData::default();
+    };
+
+    let e = match SaveData::from_json(&contents) {
+        Ok(save_data) => return save_data,
+        Err(e) => e,
+    };
+    error!("Failed to load save data: {}", e);
+
+    let mut save_data = SaveData {
+        read_only: SaveData::stored_version(&contents)
+            .is_some_and(|version| version > SAVE_VERSION),
+        ..default()
+    };
+    match write_storage(SAVE_BACKUP_KEY, &contents) {
+        Ok(()) => error!("Backed up the unreadable save to '{}'", SAVE_BACKUP_KEY),
+        Err(e) => {
+            error!("Failed to back up the unreadable save: {}", e);
+            save_data.read_only = true;
+        }
+    }
+    if save_data.read_only {
+        error!("Progress will not be saved this session");
+    }
+    save_data
+}
+
+/// Adds the finished run to the leaderboard and history, awards currency, then
+/// persists the save.
+pub fn record_run(
+    score: Res<Score>,
+    run_stats: Res<RunStats>,
+    selected_character: Res<SelectedCharacter>,
+    mut save_data: ResMut<SaveData>,
+    mut last_run: ResMut<LastRun>,
+    active_replay: Option<Res<ActiveReplay>>,
+) {
+    // Replays are checked against their recorded score, not added to the save.
+    if active_replay.is_some() {
+        last_run.0 = None;
+        return;
+    }
+
+    save_data.runs_played += 1;
+    let record = RunRecord {
+        run_number: save_data.runs_played,
+        hero: selected_character.0.clone().unwrap_or_default(),
+        score: score.0,
+        time_survived: run_stats.time_survived,
+        level: run_stats.level_reached,
+        kills: run_stats.total_kills(),
+        currency_earned: currency_for_run(score.0, run_stats.time_survived),
+    };
+    save_data.meta.currency += record.currency_earned;
+    save_data.add_run(record.clone());
+    last_run.0 = Some(record);
+
+    write_save_data(&save_data);
+}
+
+pub fn write_save_data(save_data: &SaveData) {
+    if save_data.read_only {
+        return;
+    }
+    if let Err(e) = save_data
+        .to_json()
+        .and_then(|json| write_storage(SAVE_KEY, &json))
+    {
+        warn!("Failed to write save data: {}", e);
+    }
+}
+
+/// Upgrades a save from `version` to `version + 1` in place.
+fn migrate(value: &mut serde_json::Value, version: u32) -> anyhow::Result<()> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("save data is not an object"))?;
+
+    match version {
+        // v2 added meta-progression.
+        1 => {
+            object.insert(
+                "meta".to_string(),
+                serde_json::to_value(MetaProgression::default())?,
+            );
+        }
+        v => anyhow::bail!("no migration from save version {}", v),
+    }
+    object.insert("version".to_string(), (version + 1).into());
+    Ok(())
+}
+
+fn version_of(value: &serde_json::Value) -> anyhow::Result<u32> {
+    value
+        .get("version")
+        .and_then(|v| v.as_u64())
+        .and_then(|v| u32::try_from(v).ok())
+        .ok_or_else(|| anyhow::anyhow!("save data has no version"))
+}
+
+impl SaveData {
+    pub fn from_json(contents: &str) -> anyhow::Result<Self> {
+        let mut value: serde_json::Value = serde_json::from_str(contents)?;
+        let mut version = version_of(&value)?;
+
+        if version == 0 || version > SAVE_VERSION {
+            anyhow::bail!("unsupported save version {}", version);
+        }
+        while version < SAVE_VERSION {
+            migrate(&mut value, version)?;
+            version += 1;
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// The version a stored save was written with, even if it cannot be loaded.
+    pub fn stored_version(contents: &str) -> Option<u32> {
+        let value = serde_json::from_str(contents).ok()?;
+        version_of(&value).ok()
+    }
+
+    pub fn to_json(&self) -> anyhow::Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn add_run(&mut self, record: RunRecord) {
+        self.history.push_front(record.clone());
+        self.history.truncate(MAX_RUN_HISTORY);
+
+        if record.score == 0 {
+            return;
+        }
+        let scores = self.high_scores.entry(record.hero.clone()).or_default();
+        scores.push(record);
+        scores.sort_by_key(|record| Reverse(record.score));
+        scores.truncate(MAX_HIGH_SCORES_PER_HERO);
+    }
+
+    pub fn high_scores(&self, hero: &str) -> &[RunRecord] {
+        self.high_scores
+            .get(hero)
+            .map_or(&[], |scores| scores.as_slice())
+    }
+}
+
+impl Default for SaveData {
+    fn default() -> Self {
+        Self {
+            version: SAVE_VERSION,
+            runs_played: 0,
+            high_scores: BTreeMap::new(),
+            history: VecDeque::new(),
+            meta: MetaProgression::default(),
+            read_only: false,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use bevy::ecs::schedule::ExecutorKind;
+use bevy::prelude::*;
+use bevy::transform::TransformSystem;
+
+use crate::bridge::RequestedSeed;
+use crate::player::{Health, Player};
+use crate::resources::{reset_run_resources, seed_run_rng, RunRng, RunStats, Score};
+use crate::state::GameState;
+
+/// Simulation tick rate. Everything that affects the outcome of a run runs in
+/// `FixedUpdate` at this rate so a run can be reproduced from its seed and inputs.
+pub const SIMULATION_HZ: f64 = 60.0;
+
+/// Ordered stages of a simulation tick.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SimSet {
+    Input,
+    Movement,
+    Combat,
+    Cleanup,
+}
+
+/// Set in `PostUpdate` that moves interpolated entities to their render position.
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InterpolationSet;
+
+/// Renders the entity between its last two simulated transforms, so movement
+/// stays smooth when the frame rate doesn't match the tick rate. The simulation
+/// only ever sees the simulated transform.
+#[derive(Component, Default)]
+pub struct InterpolatedTransform {
+    previous: Option<(Vec3, Quat)>,
+    current: Option<(Vec3, Quat)>,
+}
+
+/// Run state, fixed timestep and tick ordering shared by every gameplay plugin.
+pub struct SimulationPlugin;
+
+/// Smooths rendering of `InterpolatedTransform` entities between ticks.
+pub struct InterpolationPlugin;
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        // The multi-threaded executor may run unordered systems in a different
+        // order every tick, which breaks replays.
+        app.edit_schedule(FixedUpdate, |schedule| {
+            schedule.set_executor_kind(ExecutorKind::SingleThreaded);
+        })
+        .insert_resource(Time::<Fixed>::from_hz(SIMULATION_HZ))
+        .insert_resource(Score(0))
+        .init_resource::<RunStats>()
+        .insert_resource(RunRng::new(0))
+        .init_resource::<RequestedSeed>()
+        .add_systems(
+            OnEnter(GameState::GameInit),
+            (reset_run_resources, seed_run_rng),
+        )
+        .configure_sets(
+            FixedUpdate,
+            (
+                SimSet::Input,
+                SimSet::Movement,
+                SimSet::Combat,
+                SimSet::Cleanup,
+            )
+                .chain()
+                .run_if(in_state(GameState::InGame).and_then(player_alive)),
+        );
+    }
+}
+
+impl Plugin for InterpolationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, restore_simulated_transforms)
+            .add_systems(FixedFirst, store_previous_transforms)
+            .add_systems(
+                PostUpdate,
+                interpolate_transforms
+                    .in_set(InterpolationSet)
+                    .before(TransformSystem::TransformPropagate),
+            );
+    }
+}
+
+fn restore_simulated_transforms(mut query: Query<(&mut Transform, &mut InterpolatedTransform)>) {
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        if let Some((translation, rotation)) = interpolated.current.take() {
+            transform.translation = translation;
+            transform.rotation = rotation;
+        }
+    }
+}
+
+fn store_previous_transforms(mut query: Query<(&Transform, &mut InterpolatedTransform)>) {
+    for (transform, mut interpolated) in query.iter_mut() {
+        interpolated.previous = Some((transform.translation, transform.rotation));
+    }
+}
+
+fn interpolate_transforms(
+    fixed_time: Res<Time<Fixed>>,
+    mut query: Query<(&mut Transform, &mut InterpolatedTransform)>,
+) {
+    let alpha = fixed_time.overstep_fraction();
+    for (mut transform, mut interpolated) in query.iter_mut() {
+        let current = (transform.translation, transform.rotation);
+        let (previous_translation, previous_rotation) =
+            *interpolated.previous.get_or_insert(current);
+        interpolated.current = Some(current);
+
+        transform.translation = previous_translation.lerp(current.0, alpha);
+        transform.rotation = previous_rotation.slerp(current.1, alpha);
+    }
+}
+
+impl InterpolatedTransform {
+    /// Renders the next frame at the simulated position instead of sliding
+    /// there from the old one. Call after moving the entity by a jump.
+    pub fn teleport(&mut self) {
+        self.previous = None;
+    }
+}
+
+/// Stops the simulation on the tick the player dies, so extra ticks before the
+/// state change can't alter the result.
+pub fn player_alive(player_query: Query<&Health, With<Player>>) -> bool {
+    player_query.get_single().is_ok_and(|health| health.0 > 0.0)
+}
@@ -0,0 +1,449 @@
+use bevy::ecs::system::SystemParam;
+use bevy::utils::HashMap;
+
+use bevy::prelude::*;
+use kd_tree::{KdPoint, KdTree};
+
+use crate::enemy::{leash_enemies, Enemy, EnemyTeleportEvent};
+use crate::simulation::SimSet;
+use crate::state::GameState;
+use crate::*;
+
+/// Keeps the enemy positions behind `SpatialIndex` up to date.
+pub struct SpatialIndexPlugin;
+
+/// How far an enemy can walk between two rebuilds of the kd-tree. Queries
+/// search this much farther so enemies that walked into range are still found.
+/// Teleported enemies rebuild the tree right away instead.
+const STALE_DISTANCE: f32 = ENEMY_SPEED * KD_TREE_REFRESH_RATE;
+
+/// Which store answers `SpatialIndex` queries. Can be switched at any time,
+/// the newly selected store is rebuilt on the next tick.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SpatialBackend {
+    /// Rebuilt from scratch every `KD_TREE_REFRESH_RATE`, and on the tick an
+    /// enemy teleports.
+    #[default]
+    KdTree,
+    /// Uniform grid of `SPATIAL_HASH_CELL_SIZE` cells, updated every tick from
+    /// moved enemies and every frame from despawned ones.
+    SpatialHash,
+}
+
+/// Entity positions that can be searched by area. Results are candidates in
+/// no particular order.
+pub trait SpatialStore {
+    fn within_radius(&self, position: Vec2, radius: f32) -> Vec<Entity>;
+    fn within_rect(&self, min: Vec2, max: Vec2) -> Vec<Entity>;
+    fn nearest(&self, position: Vec2, k: usize) -> Vec<Entity>;
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+struct Collidable {
+    pos: Vec2,
+    entity: Entity,
+}
+
+#[derive(Resource)]
+pub struct EnemyKdTree(KdTree<Collidable>);
+
+#[derive(Resource)]
+pub struct SpatialHash {
+    cell_size: f32,
+    cells: HashMap<IVec2, Vec<Entity>>,
+    positions: HashMap<Entity, Vec2>,
+}
+
+/// An enemy found by a `SpatialIndex` query, at its current position.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpatialHit {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub distance: f32,
+}
+
+/// Spatial queries over living enemies. Candidates come from the store picked
+/// by `SpatialBackend`, then are checked against the enemies' current
+/// positions, so despawned enemies are never returned. Results are sorted by
+/// distance, nearest first.
+#[derive(SystemParam)]
+pub struct SpatialIndex<'w, 's> {
+    backend: Res<'w, SpatialBackend>,
+    tree: Res<'w, EnemyKdTree>,
+    hash: Res<'w, SpatialHash>,
+    enemies: Query<'w, 's, &'static Transform, With<Enemy>>,
+}
+
+impl Plugin for SpatialIndexPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpatialBackend>()
+            .insert_resource(EnemyKdTree::default())
+            .insert_resource(SpatialHash::new(SPATIAL_HASH_CELL_SIZE))
+            .add_event::<EnemyTeleportEvent>()
+            .add_systems(
+                FixedUpdate,
+                (
+                    update_enemy_kd_tree
+                        .run_if(resource_equals(SpatialBackend::KdTree))
+                        .run_if(kd_tree_is_due),
+                    update_enemy_spatial_hash.run_if(resource_equals(SpatialBackend::SpatialHash)),
+                )
+                    .in_set(SimSet::Movement)
+                    .after(leash_enemies),
+            )
+            // Removals are only readable for a couple of frames, and the
+            // simulation may not tick in those, so they are read every frame.
+            .add_systems(Last, remove_despawned_enemies)
+            .add_systems(OnExit(GameState::InGame), clear_spatial_index);
+    }
+}
+
+impl SpatialBackend {
+    /// Parses a backend name as given on the command line.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "kd-tree" => Some(SpatialBackend::KdTree),
+            "hash" => Some(SpatialBackend::SpatialHash),
+            _ => None,
+        }
+    }
+}
+
+impl SpatialIndex<'_, '_> {
+    pub fn nearest(&self, position: Vec2) -> Option<SpatialHit> {
+        self.k_nearest(position, 1).into_iter().next()
+    }
+
+    /// The store's nearest candidates may have been despawned or moved since
+    /// it was updated, so they only give a first guess at the search radius.
+    /// The radius then grows until it holds `k` living enemies.
+    pub fn k_nearest(&self, position: Vec2, k: usize) -> Vec<SpatialHit> {
+        if k == 0 {
+            return Vec::new();
+        }
+
+        let store = self.store();
+        let mut radius = self
+            .hits(position, store.nearest(position, k))
+            .last()
+            .map_or(STALE_DISTANCE, |hit| hit.distance.max(STALE_DISTANCE));
+        loop {
+            let candidates = store.within_radius(position, radius + STALE_DISTANCE);
+            let searched_everything = candidates.len() >= store.len();
+            let mut hits = self.hits(position, candidates);
+            if !searched_everything {
+                hits.retain(|hit| hit.distance <= radius);
+            }
+            if hits.len() >= k || searched_everything {
+                hits.truncate(k);
+                return hits;
+            }
+            radius *= 2.0;
+        }
+    }
+
+    pub fn within_radius(&self, position: Vec2, radius: f32) -> Vec<SpatialHit> {
+        let candidates = self
+            .store()
+            .within_radius(position, radius + STALE_DISTANCE);
+        let mut hits = self.hits(position, candidates);
+        hits.retain(|hit| hit.distance <= radius);
+        hits
+    }
+
+    /// Enemies within `radius` of `origin` and at most half of `angle` radians
+    /// away from `direction`.
+    pub fn within_cone(
+        &self,
+        origin: Vec2,
+        direction: Vec2,
+        radius: f32,
+        angle: f32,
+    ) -> Vec<SpatialHit> {
+        let direction = direction.normalize_or_zero();
+        let mut hits = self.within_radius(origin, radius);
+        hits.retain(|hit| {
+            let to_enemy = (hit.position - origin).normalize_or_zero();
+            to_enemy.dot(direction).clamp(-1.0, 1.0).acos() <= angle / 2.0
+        });
+        hits
+    }
+
+    /// Enemies inside `rect`, sorted by distance from its center.
+    pub fn within_rect(&self, rect: Rect) -> Vec<SpatialHit> {
+        let candidates = self
+            .store()
+            .within_rect(rect.min - STALE_DISTANCE, rect.max + STALE_DISTANCE);
+        let mut hits = self.hits(rect.center(), candidates);
+        hits.retain(|hit| rect.contains(hit.position));
+        hits
+    }
+
+    fn store(&self) -> &dyn SpatialStore {
+        match *self.backend {
+            SpatialBackend::KdTree => &*self.tree,
+            SpatialBackend::SpatialHash => &*self.hash,
+        }
+    }
+
+    fn hits(&self, from: Vec2, candidates: Vec<Entity>) -> Vec<SpatialHit> {
+        let mut hits: Vec<SpatialHit> = candidates
+            .into_iter()
+            .filter_map(|entity| {
+                let position = self.enemies.get(entity).ok()?.translation.truncate();
+                Some(SpatialHit {
+                    entity,
+                    position,
+                    distance: position.distance(from),
+                })
+            })
+            .collect();
+        // Entity breaks ties so runs stay reproducible
+        hits.sort_by(|a, b| {
+            a.distance
+                .total_cmp(&b.distance)
+                .then(a.entity.cmp(&b.entity))
+        });
+        hits
+    }
+}
+
+impl EnemyKdTree {
+    pub fn build(items: impl IntoIterator<Item = (Entity, Vec2)>) -> Self {
+        let items = items
+            .into_iter()
+            .map(|(entity, pos)| Collidable { pos, entity })
+            .collect();
+        Self(KdTree::build_by_ordered_float(items))
+    }
+}
+
+impl SpatialStore for EnemyKdTree {
+    fn within_radius(&self, position: Vec2, radius: f32) -> Vec<Entity> {
+        self.0
+            .within_radius(&[position.x, position.y], radius)
+            .into_iter()
+            .map(|item| item.entity)
+            .collect()
+    }
+
+    fn within_rect(&self, min: Vec2, max: Vec2) -> Vec<Entity> {
+        self.0
+            .within(&[[min.x, min.y], [max.x, max.y]])
+            .into_iter()
+            .map(|item| item.entity)
+            .collect()
+    }
+
+    fn nearest(&self, position: Vec2, k: usize) -> Vec<Entity> {
+        self.0
+            .nearests(&[position.x, position.y], k)
+            .into_iter()
+            .map(|found| found.item.entity)
+            .collect()
+    }
+
+    fn len(&self) -> usize {
+        self.0.len()
+    }
+}
+
+impl SpatialHash {
+    pub fn new(cell_size: f32) -> Self {
+        Self {
+            cell_size,
+            cells: HashMap::new(),
+            positions: HashMap::new(),
+        }
+    }
+
+    /// Adds the entity, or moves it if it is already in the hash.
+    pub fn insert(&mut self, entity: Entity, position: Vec2) {
+        let cell = self.cell(position);
+        if let Some(previous) = self.positions.insert(entity, position) {
+            let previous_cell = self.cell(previous);
+            if previous_cell == cell {
+                return;
+            }
+            self.remove_from_cell(previous_cell, entity);
+        }
+        self.cells.entry(cell).or_default().push(entity);
+    }
+
+    pub fn remove(&mut self, entity: Entity) {
+        if let Some(position) = self.positions.remove(&entity) {
+            self.remove_from_cell(self.cell(position), entity);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.positions.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.positions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.positions.is_empty()
+    }
+
+    fn cell(&self, position: Vec2) -> IVec2 {
+        (position / self.cell_size).floor().as_ivec2()
+    }
+
+    fn remove_from_cell(&mut self, cell: IVec2, entity: Entity) {
+        if let Some(entities) = self.cells.get_mut(&cell) {
+            if let Some(index) = entities.iter().position(|e| *e == entity) {
+                entities.swap_remove(index);
+            }
+            if entities.is_empty() {
+                self.cells.remove(&cell);
+            }
+        }
+    }
+
+    fn cell_entities(&self, cell: IVec2) -> impl Iterator<Item = (Entity, Vec2)> + '_ {
+        self.cells
+            .get(&cell)
+            .into_iter()
+            .flatten()
+            .map(|entity| (*entity, self.positions[entity]))
+    }
+}
+
+impl SpatialStore for SpatialHash {
+    fn within_radius(&self, position: Vec2, radius: f32) -> Vec<Entity> {
+        let radius_squared = radius * radius;
+        let mut found = self.within_rect(position - radius, position + radius);
+        found.retain(|entity| self.positions[entity].distance_squared(position) <= radius_squared);
+        found
+    }
+
+    fn within_rect(&self, min: Vec2, max: Vec2) -> Vec<Entity> {
+        let (min_cell, max_cell) = (self.cell(min), self.cell(max));
+        let mut found = Vec::new();
+        for x in min_cell.x..=max_cell.x {
+            for y in min_cell.y..=max_cell.y {
+                found.extend(
+                    self.cell_entities(IVec2::new(x, y))
+                        .filter(|(_, pos)| pos.cmpge(min).all() && pos.cmple(max).all())
+                        .map(|(entity, _)| entity),
+                );
+            }
+        }
+        found
+    }
+
+    /// Searches rings of cells around `position` until the `k` nearest are
+    /// closer than any cell not searched yet.
+    fn nearest(&self, position: Vec2, k: usize) -> Vec<Entity> {
+        let center = self.cell(position);
+        let mut found: Vec<(f32, Entity)> = Vec::new();
+        let mut seen = 0;
+        let mut ring = 0;
+        while seen < self.len() && k > 0 {
+            for x in center.x - ring..=center.x + ring {
+                for y in center.y - ring..=center.y + ring {
+                    if (x - center.x).abs() != ring && (y - center.y).abs() != ring {
+                        continue;
+                    }
+                    for (entity, pos) in self.cell_entities(IVec2::new(x, y)) {
+                        seen += 1;
+                        found.push((pos.distance(position), entity));
+                    }
+                }
+            }
+            // Cells past this ring are at least `ring` cells away
+            found.sort_by(|a, b| a.0.total_cmp(&b.0).then(a.1.cmp(&b.1)));
+            if found.len() >= k && found[k - 1].0 <= ring as f32 * self.cell_size {
+                break;
+            }
+            ring += 1;
+        }
+        found.truncate(k);
+        found.into_iter().map(|(_, entity)| entity).collect()
+    }
+
+    fn len(&self) -> usize {
+        self.positions.len()
+    }
+}
+
+/// Due every `KD_TREE_REFRESH_RATE`, or as soon as an enemy teleported, since
+/// the tree would otherwise miss it by more than `STALE_DISTANCE`.
+fn kd_tree_is_due(
+    time: Res<Time>,
+    mut timer: Local<Option<Timer>>,
+    mut teleports: EventReader<EnemyTeleportEvent>,
+) -> bool {
+    let timer = timer
+        .get_or_insert_with(|| Timer::from_seconds(KD_TREE_REFRESH_RATE, TimerMode::Repeating));
+    let refresh = timer.tick(time.delta()).just_finished();
+    let teleported = teleports.read().count() > 0;
+    refresh || teleported
+}
+
+fn update_enemy_kd_tree(
+    mut tree: ResMut<EnemyKdTree>,
+    enemy_query: Query<(&Transform, Entity), With<Enemy>>,
+) {
+    *tree = EnemyKdTree::build(
+        enemy_query
+            .iter()
+            .map(|(t, e)| (e, t.translation.truncate())),
+    );
+}
+
+fn update_enemy_spatial_hash(
+    backend: Res<SpatialBackend>,
+    mut hash: ResMut<SpatialHash>,
+    enemy_query: Query<(Entity, Ref<Transform>), With<Enemy>>,
+) {
+    // The hash missed every change while another backend was selected
+    let rebuild = backend.is_changed();
+    if rebuild {
+        hash.clear();
+    }
+
+    for (entity, transform) in enemy_query.iter() {
+        if rebuild || transform.is_changed() {
+            hash.insert(entity, transform.translation.truncate());
+        }
+    }
+}
+
+fn remove_despawned_enemies(mut hash: ResMut<SpatialHash>, mut removed: RemovedComponents<Enemy>) {
+    for entity in removed.read() {
+        hash.remove(entity);
+    }
+}
+
+/// Drops every enemy of the run that just ended.
+fn clear_spatial_index(mut tree: ResMut<EnemyKdTree>, mut hash: ResMut<SpatialHash>) {
+    *tree = EnemyKdTree::default();
+    hash.clear();
+}
+
+impl KdPoint for Collidable {
+    type Scalar = f32;
+    type Dim = typenum::U2;
+    fn at(&self, k: usize) -> f32 {
+        if k == 0 {
+            return self.pos.x;
+        }
+
+        self.pos.y
+    }
+}
+
+impl Default for EnemyKdTree {
+    fn default() -> Self {
+        Self::build([])
+    }
+}
@@ -0,0 +1,207 @@
+//! Planned spawns of `enemy_spawns.json`. Shared by `spawn_enemies_system` and the
+//! `spawn_timeline` preview tool, so the preview matches what the game spawns.
+
+use crate::enemy::{EnemySpawn, SpawnData};
+
+/// A stretch of the loop where no spawn entry is active.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpawnGap {
+    pub start: f32,
+    pub end: f32,
+}
+
+/// Two entries for the same race and power whose windows overlap, so that enemy
+/// spawns at double rate for a while.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpawnOverlap {
+    pub first: usize,
+    pub second: usize,
+    pub start: f32,
+    pub end: f32,
+}
+
+/// The spawn timeline, repeated every `loop_length` seconds with more health per loop.
+pub struct SpawnTimeline<'a> {
+    spawns: &'a [EnemySpawn],
+    loop_length: f32,
+}
+
+pub fn parse_time_to_seconds(time_str: &str) -> f32 {
+    let parts: Vec<&str> = time_str.split(':').collect();
+    let minutes: f32 = parts[0].parse().unwrap_or(0.0);
+    let seconds: f32 = parts[1].parse().unwrap_or(0.0);
+    minutes * 60.0 + seconds
+}
+
+pub fn calculate_spawn_count(spawn: &EnemySpawn, start: f32, end: f32, current: f32) -> u32 {
+    let total_time = end - start;
+    let elapsed_time = current - start;
+    let spawn_ratio = elapsed_time / total_time;
+    (spawn.count as f32 * spawn_ratio) as u32
+}
+
+/// Enemy health multiplier applied to every spawn during the given loop.
+pub fn loop_health_multiplier(loop_index: u32) -> u32 {
+    1 + (1.2 * loop_index as f32).floor() as u32
+}
+
+impl<'a> SpawnTimeline<'a> {
+    pub fn new(spawn_data: &'a SpawnData) -> Self {
+        let spawns = spawn_data.enemy_spawns.as_slice();
+        let loop_length = spawns
+            .iter()
+            .map(|spawn| parse_time_to_seconds(&spawn.end_time))
+            .fold(0.0, f32::max);
+        Self {
+            spawns,
+            loop_length,
+        }
+    }
+
+    pub fn spawns(&self) -> &'a [EnemySpawn] {
+        self.spawns
+    }
+
+    /// Seconds until the timeline starts over.
+    pub fn loop_length(&self) -> f32 {
+        self.loop_length
+    }
+
+    /// Zero-based loop playing at `time`.
+    pub fn loop_index(&self, time: f32) -> u32 {
+        if self.loop_length <= 0.0 {
+            return 0;
+        }
+        (time / self.loop_length).floor() as u32
+    }
+
+    /// Start and end of an entry within a loop, in seconds.
+    pub fn window(spawn: &EnemySpawn) -> (f32, f32) {
+        (
+            parse_time_to_seconds(&spawn.start_time),
+            parse_time_to_seconds(&spawn.end_time),
+        )
+    }
+
+    /// Enemies per second an entry spawns while its window is open.
+    pub fn rate(spawn: &EnemySpawn) -> f32 {
+        let (start, end) = Self::window(spawn);
+        if end <= start {
+            return 0.0;
+        }
+        spawn.count as f32 / (end - start)
+    }
+
+    /// Start and end of the entry's window in the loop playing at `time`, in
+    /// seconds since the run started, if the entry is spawning at `time`.
+    /// Windows include their start but not their end, so an entry ending
+    /// when another starts never spawns alongside it.
+    pub fn active_window(&self, spawn: &EnemySpawn, time: f32) -> Option<(f32, f32)> {
+        let (start, end) = Self::window(spawn);
+        let offset = self.loop_index(time) as f32 * self.loop_length;
+        let (start, end) = (start + offset, end + offset);
+        (time >= start && time < end).then_some((start, end))
+    }
+
+    /// Whether the entry is spawning at `time`, in any loop.
+    pub fn is_active(&self, spawn: &EnemySpawn, time: f32) -> bool {
+        self.active_window(spawn, time).is_some()
+    }
+
+    /// Total enemies per second spawned at `time`.
+    pub fn total_rate(&self, time: f32) -> f32 {
+        self.spawns
+            .iter()
+            .filter(|spawn| self.is_active(spawn, time))
+            .map(Self::rate)
+            .sum()
+    }
+
+    /// Enemies an entry has spawned by `time`, over every loop so far.
+    pub fn planned_count(&self, spawn: &EnemySpawn, time: f32) -> u32 {
+        let (start, end) = Self::window(spawn);
+        (0..=self.loop_index(time))
+            .map(|loop_index| {
+                let offset = loop_index as f32 * self.loop_length;
+                let (start, end) = (start + offset, end + offset);
+                if time < start {
+                    0
+                } else {
+                    calculate_spawn_count(spawn, start, end, time.min(end))
+                }
+            })
+            .sum()
+    }
+
+    /// Enemies on the field at `time` if the player never kills anything.
+    pub fn planned_total(&self, time: f32) -> u32 {
+        self.spawns
+            .iter()
+            .map(|spawn| self.planned_count(spawn, time))
+            .sum()
+    }
+
+    /// Highest total spawn rate within a loop and when it first happens.
+    pub fn peak_rate(&self) -> (f32, f32) {
+        // The rate only changes when a window opens or closes.
+        self.spawns
+            .iter()
+            .flat_map(|spawn| {
+                let (start, end) = Self::window(spawn);
+                [start, end]
+            })
+            .filter(|time| *time < self.loop_length)
+            .map(|time| (time, self.total_rate(time)))
+            .fold((0.0, 0.0), |peak, candidate| {
+                if candidate.1 > peak.1 {
+                    candidate
+                } else {
+                    peak
+                }
+            })
+    }
+
+    /// Stretches of a loop where nothing spawns.
+    pub fn gaps(&self) -> Vec<SpawnGap> {
+        let mut windows: Vec<(f32, f32)> = self.spawns.iter().map(Self::window).collect();
+        windows.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        let mut gaps = Vec::new();
+        let mut covered_until = 0.0;
+        for (start, end) in windows {
+            if start > covered_until {
+                gaps.push(SpawnGap {
+                    start: covered_until,
+                    end: start,
+                });
+            }
+            covered_until = f32::max(covered_until, end);
+        }
+        gaps
+    }
+
+    /// Entries for the same race and power whose windows overlap.
+    pub fn overlaps(&self) -> Vec<SpawnOverlap> {
+        let mut overlaps = Vec::new();
+        for (first, a) in self.spawns.iter().enumerate() {
+            for (second, b) in self.spawns.iter().enumerate().skip(first + 1) {
+                if a.race != b.race || a.power != b.power {
+                    continue;
+                }
+                let (a_start, a_end) = Self::window(a);
+                let (b_start, b_end) = Self::window(b);
+                let start = a_start.max(b_start);
+                let end = a_end.min(b_end);
+                if start < end {
+                    overlaps.push(SpawnOverlap {
+                        first,
+                        second,
+                        start,
+                        end,
+                    });
+                }
+            }
+        }
+        overlaps
+    }
+}
@@ -8,4 +8,5 @@ pub enum GameState {
     GameInit,
     UpgradeMenu,
     InGame,
+    GameOver,
 }
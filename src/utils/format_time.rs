@@ -0,0 +1,4 @@
+pub fn format_time(seconds: f32) -> String {
+    let total_seconds = seconds.max(0.0) as u32;
+    format!("{:02}:{:02}", total_seconds / 60, total_seconds % 60)
+}
@@ -3,3 +3,6 @@ pub use scale_value::scale_value;
 
 mod ease_in_out_quint;
 pub use ease_in_out_quint::ease_in_out_quint;
+
+mod format_time;
+pub use format_time::format_time;
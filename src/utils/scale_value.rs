@@ -1,5 +1,5 @@
 pub fn scale_value(input: f32, max: f32) -> f32 {
-    if input < 0.0 || input > 1.0 {
+    if !(0.0..=1.0).contains(&input) {
         panic!("Input value must be between 0 and 1");
     }
     if max <= 1.0 {
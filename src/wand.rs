@@ -9,16 +9,20 @@ use crate::audio::{LightningEffectHandle, LightningSoundEffect};
 use crate::enemy::Enemy;
 use crate::hit_textures::HitTextureAtlas;
 use crate::lightning_hit_bundle::{LightningHit, LightningHitBundle};
-use crate::player::Player;
+use crate::player::{Player, PlayerInput};
+use crate::simulation::SimSet;
+use crate::spatial_index::SpatialIndex;
 use crate::state::GameState;
 use crate::*;
 use bevy::audio::Volume;
 
-use self::animation::AnimationIndices;
 use self::player::Level;
 
 pub struct WandPlugin;
 
+/// Lightning beams and hit effects for the events sent by `WandPlugin`.
+pub struct WandEffectsPlugin;
+
 #[derive(Event)]
 pub struct DamageEvent {
     pub target: Entity,
@@ -32,6 +36,18 @@ pub struct SecondaryArc {
     pub damage_event: DamageEvent,
 }
 
+/// A lightning beam between two points, sent when the wand or an arc hits.
+#[derive(Event)]
+pub struct LightningStrikeEvent {
+    pub from: Vec2,
+    pub to: Vec2,
+}
+
+#[derive(Event)]
+pub struct EnemyHitEvent {
+    pub position: Vec3,
+}
+
 #[derive(Component)]
 pub struct LightningEffect {
     pub lifetime: f32,
@@ -48,53 +64,60 @@ impl Plugin for WandPlugin {
     fn build(&self, app: &mut App) {
         app.add_event::<DamageEvent>()
             .add_event::<SecondaryArc>()
+            .add_event::<LightningStrikeEvent>()
+            .add_event::<EnemyHitEvent>()
             .add_systems(
-                Update,
+                FixedUpdate,
                 (
-                    update_wand_transform,
-                    handle_wand_input,
-                    apply_damage,
-                    secondary_arc,
-                    despawn_lightning,
-                )
-                    .run_if(in_state(GameState::InGame)),
+                    update_wand_transform.in_set(SimSet::Movement),
+                    (handle_wand_input, apply_damage, secondary_arc)
+                        .chain()
+                        .in_set(SimSet::Combat),
+                ),
             );
     }
 }
 
+impl Plugin for WandEffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                spawn_lightning_strikes,
+                spawn_hit_effects,
+                despawn_lightning,
+            )
+                .run_if(in_state(GameState::InGame)),
+        );
+    }
+}
+
 fn secondary_arc(
-    mut commands: Commands,
-    mut mesh_assets: ResMut<Assets<Mesh>>,
-    mut material_assets: ResMut<Assets<ColorMaterial>>,
     mut secondary_arc_events: EventReader<SecondaryArc>,
     mut damage_events: EventWriter<DamageEvent>,
-    mut enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+    mut lightning_events: EventWriter<LightningStrikeEvent>,
+    enemy_query: Query<&Transform, With<Enemy>>,
+    spatial_index: SpatialIndex,
 ) {
     for event in secondary_arc_events.read() {
-        if let Ok((_entity, &transform)) = enemy_query.get_mut(event.damage_event.target) {
+        if let Ok(transform) = enemy_query.get(event.damage_event.target) {
             let remaining_arcs = event.damage_event.arcs - 1;
             if remaining_arcs > 0 {
-                if let Some((target, target_transform)) = get_nearest_enemy_in_radius(
-                    &enemy_query,
-                    transform.translation.truncate(),
-                    150.0,
-                    event.from_target,
-                ) {
+                if let Some(hit) = spatial_index
+                    .within_radius(transform.translation.truncate(), 150.0)
+                    .into_iter()
+                    .find(|hit| hit.entity != event.from_target)
+                {
                     damage_events.send(DamageEvent {
-                        target,
+                        target: hit.entity,
                         amount: event.damage_event.amount,
                         arcs: remaining_arcs,
                     });
 
-                    draw_vector_path(
-                        &mut commands,
-                        &mut mesh_assets,
-                        &mut material_assets,
-                        transform.translation.truncate(),
-                        target_transform.translation.truncate(),
-                        Color::YELLOW,
-                        2.0,
-                    );
+                    lightning_events.send(LightningStrikeEvent {
+                        from: transform.translation.truncate(),
+                        to: hit.position,
+                    });
                 }
             }
         }
@@ -102,24 +125,20 @@ fn secondary_arc(
 }
 
 fn apply_damage(
-    mut commands: Commands,
-    hit_texture_atlas: Res<HitTextureAtlas>,
     mut damage_events: EventReader<DamageEvent>,
     mut secondary_arc_events: EventWriter<SecondaryArc>,
+    mut hit_events: EventWriter<EnemyHitEvent>,
     mut enemy_query: Query<(&Transform, &mut Enemy), With<Enemy>>,
-    lightning_sound: ResMut<LightningEffectHandle>,
+    mut run_stats: ResMut<RunStats>,
 ) {
     for event in damage_events.read() {
         if let Ok((transform, mut enemy)) = enemy_query.get_mut(event.target) {
             // if # of arcs is > 0 then spawn another event and choose a random enemy to target nearby
+            run_stats.damage_dealt += event.amount.min(enemy.current_health.max(0.0));
             enemy.current_health -= event.amount;
-            commands
-                .spawn(LightningHitBundle::new(
-                    &hit_texture_atlas,
-                    AnimationIndices { first: 0, last: 3 },
-                    transform.translation.clone(),
-                ))
-                .insert(LightningHit);
+            hit_events.send(EnemyHitEvent {
+                position: transform.translation,
+            });
 
             secondary_arc_events.send(SecondaryArc {
                 from_target: event.target,
@@ -129,19 +148,56 @@ fn apply_damage(
                     arcs: event.arcs,
                 },
             });
+        }
+    }
+}
 
-            commands.spawn((
-                AudioBundle {
-                    source: lightning_sound.handle.clone(),
-                    settings: PlaybackSettings {
-                        volume: Volume::new(0.8),
-                        ..default()
-                    },
+fn spawn_hit_effects(
+    mut commands: Commands,
+    hit_texture_atlas: Res<HitTextureAtlas>,
+    lightning_sound: Res<LightningEffectHandle>,
+    mut hit_events: EventReader<EnemyHitEvent>,
+) {
+    for event in hit_events.read() {
+        if let Some(clip) = &hit_texture_atlas.lightning_clip {
+            commands
+                .spawn(LightningHitBundle::new(
+                    &hit_texture_atlas,
+                    clip.clone(),
+                    event.position,
+                ))
+                .insert(LightningHit);
+        }
+
+        commands.spawn((
+            AudioBundle {
+                source: lightning_sound.handle.clone(),
+                settings: PlaybackSettings {
+                    volume: Volume::new(0.8),
                     ..default()
                 },
-                LightningSoundEffect,
-            ));
-        }
+            },
+            LightningSoundEffect,
+        ));
+    }
+}
+
+fn spawn_lightning_strikes(
+    mut commands: Commands,
+    mut mesh_assets: ResMut<Assets<Mesh>>,
+    mut material_assets: ResMut<Assets<ColorMaterial>>,
+    mut lightning_events: EventReader<LightningStrikeEvent>,
+) {
+    for event in lightning_events.read() {
+        draw_vector_path(
+            &mut commands,
+            &mut mesh_assets,
+            &mut material_assets,
+            event.from,
+            event.to,
+            Color::YELLOW,
+            2.0,
+        );
     }
 }
 
@@ -151,7 +207,7 @@ fn despawn_lightning(
     time: Res<Time>,
 ) {
     for (mut effect, entity) in lightning_query.iter_mut() {
-        effect.lifetime = effect.lifetime - time.delta().as_millis() as f32;
+        effect.lifetime -= time.delta().as_millis() as f32;
         if effect.lifetime <= 0.0 {
             commands.entity(entity).despawn();
         }
@@ -159,7 +215,7 @@ fn despawn_lightning(
 }
 
 fn update_wand_transform(
-    cursor_pos: Res<CursorPosition>,
+    input: Res<PlayerInput>,
     player_query: Query<&Transform, With<Player>>,
     mut wand_query: Query<&mut Transform, (With<Wand>, Without<Player>)>,
 ) {
@@ -168,10 +224,7 @@ fn update_wand_transform(
     }
 
     let player_pos = player_query.single().translation.truncate();
-    let cursor_pos = match cursor_pos.0 {
-        Some(pos) => pos,
-        None => player_pos,
-    };
+    let cursor_pos = input.aim.unwrap_or(player_pos);
     let mut wand_transform = wand_query.single_mut();
 
     let angle = (player_pos.y - cursor_pos.y).atan2(player_pos.x - cursor_pos.x) + PI;
@@ -188,119 +241,51 @@ fn update_wand_transform(
 }
 
 fn handle_wand_input(
-    mut commands: Commands,
-    mut mesh_assets: ResMut<Assets<Mesh>>,
-    mut material_assets: ResMut<Assets<ColorMaterial>>,
     time: Res<Time>,
     mut wand_query: Query<(&Transform, &mut WandTimer), With<Wand>>,
-    mouse_button_input: Res<ButtonInput<MouseButton>>,
-    enemy_query: Query<(Entity, &Transform), With<Enemy>>,
+    input: Res<PlayerInput>,
+    spatial_index: SpatialIndex,
     player_query: Query<(&Transform, &Level), With<Player>>,
-    cursor_pos: Res<CursorPosition>,
     mut damage_events: EventWriter<DamageEvent>,
+    mut lightning_events: EventWriter<LightningStrikeEvent>,
 ) {
-    if wand_query.is_empty() || player_query.is_empty() || cursor_pos.0.is_none() {
+    if wand_query.is_empty() || player_query.is_empty() {
         return;
     }
+    let Some(aim) = input.aim else {
+        return;
+    };
 
     let (player_transform, player_level) = player_query.single();
     let (wand_transform, mut wand_timer) = wand_query.single_mut();
     wand_timer.0.tick(time.delta());
 
-    if !mouse_button_input.pressed(MouseButton::Left) {
+    if !input.cast {
         return;
     }
 
     if wand_timer.0.elapsed_secs() >= BULLET_SPAWN_INTERVAL {
         wand_timer.0.reset();
-        let target = get_enemies_in_cone(
-            player_transform,
-            &enemy_query,
-            &cursor_pos.0.unwrap(),
-            300.0,
-            90.0,
-        );
+        let player_pos = player_transform.translation.truncate();
+        let target = spatial_index
+            .within_cone(player_pos, aim - player_pos, 300.0, 90f32.to_radians())
+            .into_iter()
+            .next();
         if let Some(target) = target {
             damage_events.send(DamageEvent {
-                target,
+                target: target.entity,
                 amount: 5.0 * (1.0 + ((player_level.0 - 1) as f32 * 0.05)),
                 arcs: 3,
             });
 
-            if let Ok((_, target_transform)) = enemy_query.get(target) {
-                draw_vector_path(
-                    &mut commands,
-                    &mut mesh_assets,
-                    &mut material_assets,
-                    wand_transform.translation.truncate(),
-                    target_transform.translation.truncate(),
-                    Color::YELLOW,
-                    2.0,
-                );
-            }
+            lightning_events.send(LightningStrikeEvent {
+                from: wand_transform.translation.truncate(),
+                to: target.position,
+            });
         }
     }
 }
 
-fn get_enemies_in_cone(
-    player_transform: &Transform,
-    enemy_query: &Query<(Entity, &Transform), With<Enemy>>,
-    cursor_pos: &Vec2,
-    max_distance: f32,
-    cone_angle_degrees: f32,
-) -> Option<Entity> {
-    let player_pos = player_transform.translation.truncate();
-    let to_cursor = (*cursor_pos - player_pos).normalize();
-    let cone_angle_radians = cone_angle_degrees.to_radians();
-
-    enemy_query
-        .iter()
-        .filter_map(|(entity, enemy_transform)| {
-            let enemy_pos = enemy_transform.translation.truncate();
-            let to_enemy = enemy_pos - player_pos;
-            let distance = to_enemy.length();
-
-            if distance <= max_distance {
-                let angle = to_enemy.normalize().dot(to_cursor).acos();
-                if angle <= cone_angle_radians / 2.0 {
-                    Some((entity, distance))
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        })
-        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
-        .map(|(entity, _)| entity)
-}
-
-fn get_nearest_enemy_in_radius(
-    enemy_query: &Query<(Entity, &Transform), With<Enemy>>,
-    position: Vec2,
-    radius: f32,
-    exclude_entity: Entity,
-) -> Option<(Entity, Transform)> {
-    enemy_query
-        .iter()
-        .filter_map(|(entity, enemy_transform)| {
-            if entity == exclude_entity {
-                return None;
-            }
-
-            let enemy_pos = enemy_transform.translation.truncate();
-            let distance = enemy_pos.distance(position);
-
-            if distance <= radius {
-                Some((entity, distance, enemy_transform.clone()))
-            } else {
-                None
-            }
-        })
-        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
-        .map(|(entity, _, enemy_transform)| (entity, enemy_transform))
-}
-
 fn draw_vector_path(
     commands: &mut Commands,
     mesh_assets: &mut ResMut<Assets<Mesh>>,
@@ -1,88 +1,209 @@
-use bevy::math::vec3;
+use bevy::math::{vec2, vec3};
 use bevy::prelude::*;
 use bevy::time::Stopwatch;
-use rand::Rng;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
 
-use crate::animation::AnimationTimer;
-use crate::player::{Experience, Health, Level, Player, PlayerState};
+use crate::animation::AnimationPlayer;
+use crate::collision::{CollisionLayer, Hitbox};
+use crate::hero_registry::{Hero, HeroRegistry, HeroStats};
+use crate::player::{
+    Experience, ExperienceMultiplier, Health, Level, MaxHealth, MoveSpeed, Player, PlayerState,
+    RerollCharges,
+};
+use crate::replay::RunSetup;
+use crate::resources::{reset_run_resources, seed_run_rng, RunRng, RunStats};
+use crate::simulation::InterpolatedTransform;
 use crate::wand::{Wand, WandTimer};
 use crate::*;
-use crate::{state::GameState, GlobalTextureAtlas, HeroTextureAtlases};
+use crate::{state::GameState, GlobalTextureAtlas};
 
 pub struct WorldPlugin;
 
+/// Sprites for the hero, wand and world decorations.
+pub struct WorldRenderPlugin;
+
 #[derive(Component)]
 pub struct GameEntity;
 
+pub const DEFAULT_HERO: &str = "wizzard-m";
+
 #[derive(Resource)]
 pub struct SelectedCharacter(pub Option<String>);
 
+/// Hero the current run was started with, checked against the hero registry.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct RunHero(pub String);
+
+/// Cosmetic tint applied to the hero sprite.
+#[derive(Resource, Default)]
+pub struct SelectedCosmetic(pub Option<String>);
+
 impl Default for SelectedCharacter {
     fn default() -> Self {
-        SelectedCharacter(Some("wizzard-m".to_string()))
+        SelectedCharacter(Some(DEFAULT_HERO.to_string()))
+    }
+}
+
+pub fn cosmetic_tint(cosmetic: &str) -> Option<Color> {
+    match cosmetic {
+        "none" => Some(Color::WHITE),
+        "gold" => Some(Color::GOLD),
+        "frost" => Some(Color::rgb(0.6, 0.8, 1.0)),
+        "ember" => Some(Color::rgb(1.0, 0.6, 0.5)),
+        "shadow" => Some(Color::rgb(0.5, 0.5, 0.6)),
+        _ => None,
     }
 }
 
 impl Plugin for WorldPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SelectedCharacter>()
+            .add_systems(
+                OnEnter(GameState::GameInit),
+                init_world.after(reset_run_resources).after(seed_run_rng),
+            )
+            .add_systems(OnExit(GameState::InGame), despawn_all_game_entities);
+    }
+}
+
+impl Plugin for WorldRenderPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             OnEnter(GameState::GameInit),
-            (init_world, spawn_world_decorations),
-        )
-        .add_systems(OnExit(GameState::InGame), despawn_all_game_entities);
+            (
+                (attach_hero_sprite, attach_wand_sprite).after(init_world),
+                spawn_world_decorations.after(seed_run_rng),
+            ),
+        );
     }
 }
 
-fn init_world(
+pub fn init_world(
     mut commands: Commands,
-    handle: Res<GlobalTextureAtlas>,
-    selected_character: Res<SelectedCharacter>,
-    hero_atlases: Res<HeroTextureAtlases>,
+    run_setup: RunSetup,
+    hero_registry: Option<Res<HeroRegistry>>,
+    mut run_stats: ResMut<RunStats>,
     mut next_state: ResMut<NextState<GameState>>,
 ) {
-    let hero = selected_character.0.as_ref().unwrap();
-    let hero_texture_atlas = hero_atlases.get_hero(hero);
+    // Headless runs have no hero registry and take the hero as given.
+    let (hero, stats) = match hero_registry {
+        Some(hero_registry) => match resolve_hero(&hero_registry, run_setup.hero()) {
+            Some(hero) => (hero.definition.id.clone(), hero.definition.stats),
+            None => {
+                error!("No hero could be spawned, returning to the main menu");
+                commands.remove_resource::<RunHero>();
+                next_state.set(GameState::MainMenu);
+                return;
+            }
+        },
+        None => (run_setup.hero().to_string(), HeroStats::default()),
+    };
+    commands.insert_resource(RunHero(hero));
+    run_stats.upgrades_taken = run_setup.upgrades();
 
-    if let Some(hero_texture_atlas) = hero_texture_atlas {
-        commands.spawn((
-            SpriteSheetBundle {
-                texture: hero_texture_atlas.image.clone().unwrap(),
-                atlas: TextureAtlas {
-                    layout: hero_texture_atlas.layout.clone().unwrap(),
-                    index: 0,
-                },
-                transform: Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
+    let bonuses = run_setup.bonuses();
+    let max_health = stats.health + bonuses.extra_health;
+
+    commands.spawn((
+        TransformBundle::from_transform(Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR))),
+        Player,
+        Health(max_health),
+        MaxHealth(max_health),
+        Experience(0.0),
+        MoveSpeed(stats.speed),
+        ExperienceMultiplier(bonuses.experience_multiplier),
+        RerollCharges(bonuses.reroll_charges),
+        Level(1),
+        PlayerState::default(),
+        Hitbox::circle(PLAYER_HITBOX_RADIUS).with_offset(vec2(0.0, PLAYER_HITBOX_OFFSET_Y)),
+        CollisionLayer::Player,
+        InterpolatedTransform::default(),
+        GameEntity,
+    ));
+    commands.spawn((
+        TransformBundle::from_transform(Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR))),
+        Wand,
+        WandTimer(Stopwatch::new()),
+        InterpolatedTransform::default(),
+        GameEntity,
+    ));
+
+    next_state.set(GameState::InGame);
+}
+
+/// The requested hero, or [`DEFAULT_HERO`] if the registry doesn't have it.
+fn resolve_hero<'a>(hero_registry: &'a HeroRegistry, hero: &str) -> Option<&'a Hero> {
+    if let Ok(hero) = hero_registry.get(hero) {
+        return Some(hero);
+    }
+    error!(
+        "Cannot spawn unknown hero '{}', falling back to '{}'",
+        hero, DEFAULT_HERO
+    );
+    hero_registry.get(DEFAULT_HERO).ok()
+}
+
+fn attach_hero_sprite(
+    mut commands: Commands,
+    run_hero: Option<Res<RunHero>>,
+    selected_cosmetic: Res<SelectedCosmetic>,
+    hero_registry: Res<HeroRegistry>,
+    player_query: Query<Entity, With<Player>>,
+) {
+    let Some(hero) = run_hero.and_then(|run_hero| hero_registry.get(&run_hero.0).ok()) else {
+        return;
+    };
+    let tint = selected_cosmetic
+        .0
+        .as_deref()
+        .and_then(cosmetic_tint)
+        .unwrap_or(Color::WHITE);
+
+    for entity in player_query.iter() {
+        commands.entity(entity).insert((
+            Sprite {
+                color: tint,
                 ..default()
             },
-            Player,
-            Health(PLAYER_HEALTH),
-            Experience(0.0),
-            Level(1),
-            PlayerState::default(),
-            AnimationTimer(Timer::from_seconds(0.15, TimerMode::Repeating)),
-            GameEntity,
-        ));
-        commands.spawn((
-            SpriteSheetBundle {
-                texture: handle.image.clone().unwrap(),
-                atlas: TextureAtlas {
-                    layout: handle.layout.clone().unwrap(),
-                    index: 17,
-                },
-                transform: Transform::from_scale(Vec3::splat(SPRITE_SCALE_FACTOR)),
-                ..default()
+            hero.atlas.image.clone().unwrap(),
+            TextureAtlas {
+                layout: hero.atlas.layout.clone().unwrap(),
+                index: hero.idle_clip().frames.first,
             },
-            Wand,
-            WandTimer(Stopwatch::new()),
-            GameEntity,
+            VisibilityBundle::default(),
+            AnimationPlayer::new(hero.idle_clip().clone()),
+            hero.clips.clone(),
         ));
+    }
+}
 
-        next_state.set(GameState::InGame);
+fn attach_wand_sprite(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    wand_query: Query<Entity, With<Wand>>,
+) {
+    for entity in wand_query.iter() {
+        commands.entity(entity).insert((
+            Sprite::default(),
+            handle.image.clone().unwrap(),
+            TextureAtlas {
+                layout: handle.layout.clone().unwrap(),
+                index: 17,
+            },
+            VisibilityBundle::default(),
+        ));
     }
 }
 
-fn spawn_world_decorations(mut commands: Commands, handle: Res<GlobalTextureAtlas>) {
-    let mut rng = rand::thread_rng();
+/// Decorations get their own generator derived from the run seed, so headless
+/// runs without them still draw the same numbers from `RunRng`.
+fn spawn_world_decorations(
+    mut commands: Commands,
+    handle: Res<GlobalTextureAtlas>,
+    run_rng: Res<RunRng>,
+) {
+    let mut rng = ChaCha8Rng::seed_from_u64(run_rng.seed());
     for _ in 0..NUM_WORLD_DECORATIONS {
         let x = rng.gen_range(-WORLD_W..WORLD_W);
         let y = rng.gen_range(-WORLD_H..WORLD_H);
@@ -0,0 +1,48 @@
+use std::time::Duration;
+
+use eternal_gauntlet::animation::{AnimationClip, AnimationIndices, AnimationPlayer, PlayMode};
+
+fn clip(name: &str, first: usize, last: usize, mode: PlayMode) -> AnimationClip {
+    AnimationClip {
+        name: name.to_string(),
+        frames: AnimationIndices { first, last },
+        fps: 10.0,
+        mode,
+    }
+}
+
+fn play(player: &mut AnimationPlayer, frames: usize) -> Vec<(usize, Option<String>)> {
+    (0..frames)
+        .map(|_| {
+            let finished = player.tick(Duration::from_secs_f32(0.1));
+            (player.index(), finished)
+        })
+        .collect()
+}
+
+#[test]
+fn clips_follow_their_play_mode() {
+    let mut looping = AnimationPlayer::new(clip("run", 4, 6, PlayMode::Loop));
+    let indices: Vec<usize> = play(&mut looping, 4).into_iter().map(|(i, _)| i).collect();
+    assert_eq!(indices, [5, 6, 4, 5]);
+
+    let mut ping_pong = AnimationPlayer::new(clip("idle", 0, 2, PlayMode::PingPong));
+    let indices: Vec<usize> = play(&mut ping_pong, 5)
+        .into_iter()
+        .map(|(i, _)| i)
+        .collect();
+    assert_eq!(indices, [1, 2, 1, 0, 1]);
+
+    let mut once = AnimationPlayer::new(clip("hurt", 8, 9, PlayMode::Once)).then(clip(
+        "idle",
+        0,
+        2,
+        PlayMode::Loop,
+    ));
+    assert_eq!(
+        play(&mut once, 3),
+        [(9, None), (0, Some("hurt".to_string())), (1, None)]
+    );
+    assert_eq!(once.clip().name, "idle");
+    assert!(!once.is_finished());
+}
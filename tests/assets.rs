@@ -0,0 +1,167 @@
+use std::fs;
+use std::path::Path;
+
+use bevy::math::{Rect, Vec2};
+use eternal_gauntlet::collision::Hitbox;
+use eternal_gauntlet::enemy::{EnemiesData, SpawnData, SpawnDataError};
+use eternal_gauntlet::generate_texture_atlas::{pack_folders, SerializableTextureAtlasLayout};
+use eternal_gauntlet::hero_registry::{HeroManifest, REQUIRED_HERO_CLIPS};
+use eternal_gauntlet::hit_textures::LIGHTNING_HIT_CLIP;
+use eternal_gauntlet::MAX_ENEMY_HITBOX_REACH;
+
+fn read_asset<T: serde::de::DeserializeOwned>(name: &str) -> T {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("assets")
+        .join(name);
+    serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap()
+}
+
+#[test]
+fn every_enemy_clip_resolves() {
+    let enemies: EnemiesData = read_asset("enemies.json");
+    let layout: SerializableTextureAtlasLayout = read_asset("enemy_texture_atlas_layout.json");
+    assert_eq!(layout.names.len(), layout.textures.len());
+
+    for (race, definition) in &enemies.races {
+        for (tier, enemy_tier) in &definition.tiers {
+            let clip = enemy_tier
+                .clip
+                .resolve(&layout)
+                .unwrap_or_else(|e| panic!("{race} {tier}: {e}"));
+            assert_eq!(
+                clip.frames.last - clip.frames.first,
+                3,
+                "{race} {tier} has 4 frames"
+            );
+        }
+    }
+}
+
+#[test]
+fn every_enemy_hitbox_fits_its_sprite() {
+    let enemies: EnemiesData = read_asset("enemies.json");
+    let layout: SerializableTextureAtlasLayout = read_asset("enemy_texture_atlas_layout.json");
+
+    for (race, definition) in &enemies.races {
+        for (tier, enemy_tier) in &definition.tiers {
+            let clip = enemy_tier.clip.resolve(&layout).unwrap();
+            let frame =
+                Rect::from_center_size(Vec2::ZERO, layout.textures[clip.frames.first].size());
+            let bounds = enemy_tier.hitbox.bounds();
+            assert_eq!(
+                frame.union(bounds),
+                frame,
+                "{race} {tier} hitbox is outside its sprite"
+            );
+            assert!(
+                enemy_tier.hitbox_reach() <= MAX_ENEMY_HITBOX_REACH,
+                "{race} {tier} reaches past MAX_ENEMY_HITBOX_REACH"
+            );
+        }
+    }
+}
+
+#[test]
+fn oversized_enemy_hitboxes_fail_validation() {
+    let mut enemies: EnemiesData = read_asset("enemies.json");
+    let spawn_data: SpawnData = read_asset("enemy_spawns.json");
+    assert!(enemies.validate(&spawn_data).is_ok());
+
+    let spawn = &spawn_data.enemy_spawns[0];
+    let tier = enemies
+        .races
+        .get_mut(&spawn.race)
+        .and_then(|race| race.tiers.get_mut(&spawn.power))
+        .unwrap();
+    tier.hitbox = Hitbox::circle(MAX_ENEMY_HITBOX_REACH);
+
+    let errors = enemies.validate(&spawn_data).unwrap_err();
+    assert!(errors.iter().any(|e| e.entry() == 0));
+    for e in errors {
+        let SpawnDataError::HitboxTooLarge { race, tier, .. } = &e else {
+            panic!("unexpected error {e}");
+        };
+        assert_eq!((race, tier), (&spawn.race, &spawn.power));
+    }
+}
+
+#[test]
+fn clips_follow_frame_names_not_atlas_order() {
+    let layout: SerializableTextureAtlasLayout = read_asset("enemy_texture_atlas_layout.json");
+    let clip = layout.clip("undead/zombie").unwrap();
+
+    assert_eq!(
+        layout.frame("undead/zombie/zombie_anim_f0"),
+        Some(clip.first)
+    );
+    assert_eq!(
+        layout.frame("undead/zombie/zombie_anim_f3"),
+        Some(clip.last)
+    );
+    assert!(layout.clip("undead/zomb").is_err());
+}
+
+#[test]
+fn clips_with_ten_or_more_frames_play_in_frame_number_order() {
+    let dir = std::env::temp_dir().join(format!("eternal-gauntlet-bat-{}", std::process::id()));
+    fs::create_dir_all(dir.join("bat")).unwrap();
+    for frame in 0..11 {
+        image::RgbaImage::new(2, 2)
+            .save(dir.join(format!("bat/bat_anim_f{frame}.png")))
+            .unwrap();
+    }
+    let atlas = pack_folders(std::slice::from_ref(&dir), 0, 256);
+    fs::remove_dir_all(&dir).unwrap();
+    let layout = atlas.unwrap().layout;
+
+    let expected: Vec<String> = (0..11)
+        .map(|frame| format!("bat/bat_anim_f{frame}"))
+        .collect();
+    assert_eq!(layout.names, expected);
+    let clip = layout.clip("bat/bat_anim").unwrap();
+    assert_eq!((clip.first, clip.last), (0, 10));
+    assert_eq!(layout.clip("bat"), Ok(clip));
+}
+
+#[test]
+fn every_hero_clip_resolves() {
+    let manifest: HeroManifest = read_asset("heroes.json");
+    let layout: SerializableTextureAtlasLayout = read_asset("hero_texture_atlas_layout.json");
+
+    for hero in &manifest.heroes {
+        assert!(
+            hero.stats.health > 0.0 && hero.stats.speed > 0.0,
+            "{}",
+            hero.id
+        );
+        for name in REQUIRED_HERO_CLIPS {
+            assert!(
+                hero.clips.contains_key(name),
+                "{} has no {name} clip",
+                hero.id
+            );
+        }
+        for (name, clip) in &hero.clips {
+            clip.resolve(&layout)
+                .unwrap_or_else(|e| panic!("{} {name}: {e}", hero.id));
+        }
+    }
+}
+
+#[test]
+fn lightning_hit_clip_resolves() {
+    let layout: SerializableTextureAtlasLayout = read_asset("hit_texture_atlas_layout.json");
+    let clip = layout.clip(LIGHTNING_HIT_CLIP).unwrap();
+    assert_eq!((clip.first, clip.last), (0, 3));
+}
+
+#[test]
+fn packing_reproduces_the_committed_layout() {
+    let assets = Path::new(env!("CARGO_MANIFEST_DIR")).join("assets");
+    let atlas = pack_folders(&[assets.join("hits")], 0, 2048).unwrap();
+    let committed: SerializableTextureAtlasLayout = read_asset("hit_texture_atlas_layout.json");
+
+    assert_eq!(atlas.layout.names, committed.names);
+    assert_eq!(atlas.layout.textures, committed.textures);
+    assert_eq!(atlas.layout.size, committed.size);
+}
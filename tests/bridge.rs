@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex, MutexGuard};
+
+use bevy::prelude::*;
+
+use eternal_gauntlet::animation::AnimationClips;
+use eternal_gauntlet::bridge::{
+    queue_host_command, HostBridge, HostBridgePlugin, HostSink, RequestedSeed, GAME_VERSION,
+};
+use eternal_gauntlet::hero_registry::{Hero, HeroDefinition, HeroRegistry, HeroStats};
+use eternal_gauntlet::resources::{GlobalTextureAtlas, RunRng, RunStats, Score};
+use eternal_gauntlet::state::GameState;
+use eternal_gauntlet::world::SelectedCharacter;
+
+/// Host commands go through one global queue, so apps reading it can't run in parallel.
+static HOST_QUEUE: Mutex<()> = Mutex::new(());
+
+/// Keeps every message sent to the host in memory.
+#[derive(Clone, Default)]
+struct MemorySink(Arc<Mutex<Vec<serde_json::Value>>>);
+
+impl HostSink for MemorySink {
+    fn send(&self, payload: &str) -> anyhow::Result<()> {
+        self.0.lock().unwrap().push(serde_json::from_str(payload)?);
+        Ok(())
+    }
+}
+
+impl MemorySink {
+    fn take(&self) -> Vec<serde_json::Value> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+fn hero(id: &str) -> Hero {
+    Hero {
+        definition: HeroDefinition {
+            id: id.to_string(),
+            display_name: id.to_string(),
+            stats: HeroStats::default(),
+            clips: BTreeMap::new(),
+        },
+        atlas: GlobalTextureAtlas {
+            image: None,
+            layout: None,
+        },
+        clips: AnimationClips::default(),
+    }
+}
+
+/// An app running the host bridge, still in `GameState::Loading`.
+fn bridge_app() -> (App, MemorySink, MutexGuard<'static, ()>) {
+    let guard = HOST_QUEUE.lock().unwrap_or_else(|e| e.into_inner());
+    let sink = MemorySink::default();
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, HostBridgePlugin))
+        .init_state::<GameState>()
+        .init_resource::<SelectedCharacter>()
+        .init_resource::<GlobalVolume>()
+        .init_resource::<HeroRegistry>()
+        .insert_resource(HostBridge::new(sink.clone()));
+    app.update();
+    (app, sink, guard)
+}
+
+/// What `build_hero_registry` does once the hero manifest has loaded.
+fn finish_loading(app: &mut App) {
+    let mut registry = app.world.resource_mut::<HeroRegistry>();
+    registry.insert(hero("wizzard-m"));
+    registry.insert(hero("elf-f"));
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::MainMenu);
+    app.update();
+}
+
+fn selected_hero(app: &App) -> Option<String> {
+    app.world.resource::<SelectedCharacter>().0.clone()
+}
+
+#[test]
+fn heroes_selected_while_loading_are_applied_once_the_registry_is_built() {
+    let (mut app, sink, _guard) = bridge_app();
+
+    queue_host_command("elf-f".to_string());
+    app.update();
+    assert_eq!(
+        *app.world.resource::<State<GameState>>().get(),
+        GameState::Loading
+    );
+
+    finish_loading(&mut app);
+    assert_eq!(selected_hero(&app).as_deref(), Some("elf-f"));
+    assert!(sink.take().is_empty());
+}
+
+#[test]
+fn unknown_heroes_selected_while_loading_are_rejected_once_the_registry_is_built() {
+    let (mut app, sink, _guard) = bridge_app();
+
+    queue_host_command(r#"{"type":"select_character","hero":"dragon"}"#.to_string());
+    app.update();
+    assert!(sink.take().is_empty());
+
+    finish_loading(&mut app);
+    assert_eq!(selected_hero(&app).as_deref(), Some("wizzard-m"));
+    let messages = sink.take();
+    assert_eq!(messages.len(), 1);
+    assert_eq!(messages[0]["type"], "command_error");
+    assert_eq!(messages[0]["message"], "unknown hero 'dragon'");
+}
+
+#[test]
+fn host_commands_configure_the_run_and_its_result_reaches_the_sink() {
+    let (mut app, sink, _guard) = bridge_app();
+    finish_loading(&mut app);
+
+    queue_host_command(r#"{"type":"select_character","hero":"elf-f"}"#.to_string());
+    queue_host_command(r#"{"type":"set_seed","seed":7}"#.to_string());
+    app.update();
+    assert_eq!(selected_hero(&app).as_deref(), Some("elf-f"));
+    assert_eq!(app.world.resource::<RequestedSeed>().0, Some(7));
+
+    let mut run_stats = RunStats {
+        time_survived: 95.5,
+        level_reached: 4,
+        ..default()
+    };
+    run_stats.kills.insert("orc".to_string(), 3);
+    app.insert_resource(Score(120))
+        .insert_resource(run_stats)
+        .insert_resource(RunRng::new(7));
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::GameOver);
+    app.update();
+
+    assert_eq!(
+        sink.take(),
+        [serde_json::json!({
+            "type": "run_result",
+            "hero": "elf-f",
+            "score": 120,
+            "time_survived": 95.5,
+            "level": 4,
+            "kills": 3,
+            "kills_by_race": { "orc": 3 },
+            "seed": 7,
+            "version": GAME_VERSION,
+        })]
+    );
+}
+
+#[test]
+fn rejected_host_commands_get_an_error_reply_with_their_payload() {
+    let (mut app, sink, _guard) = bridge_app();
+
+    let payloads = [
+        r#"{"type":"launch_rockets"}"#,
+        r#"{"type":"start_run"}"#,
+        r#"{"type":"set_volume","volume":3.0}"#,
+    ];
+    for payload in payloads {
+        queue_host_command(payload.to_string());
+    }
+    app.update();
+
+    // Each kind of command has its own handler, so replies can arrive in any order.
+    let replies: BTreeMap<String, String> = sink
+        .take()
+        .into_iter()
+        .map(|message| {
+            assert_eq!(message["type"], "command_error");
+            (
+                message["payload"].as_str().unwrap().to_string(),
+                message["message"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert_eq!(replies.len(), payloads.len());
+    assert!(replies[payloads[0]].starts_with("Invalid command"));
+    assert_eq!(replies[payloads[1]], "Command not allowed in state Loading");
+    assert_eq!(
+        replies[payloads[2]],
+        "Volume must be between 0 and 1, got 3"
+    );
+}
@@ -0,0 +1,182 @@
+mod common;
+
+use bevy::ecs::event::ManualEventReader;
+use bevy::prelude::*;
+
+use eternal_gauntlet::collision::{
+    Collider, CollisionLayer, CollisionPlugin, Hitbox, PickupContactEvent,
+    PlayerEnemyCollisionEvent, ProjectileHitEvent,
+};
+use eternal_gauntlet::spatial_index::SpatialBackend;
+
+const LAYERS: [CollisionLayer; 5] = [
+    CollisionLayer::Player,
+    CollisionLayer::Enemy,
+    CollisionLayer::PlayerProjectile,
+    CollisionLayer::EnemyProjectile,
+    CollisionLayer::Pickup,
+];
+
+fn circle(x: f32, y: f32, radius: f32) -> Collider {
+    Collider::Circle {
+        center: Vec2::new(x, y),
+        radius,
+    }
+}
+
+fn rect(min: Vec2, max: Vec2) -> Collider {
+    Collider::Rect(Rect::from_corners(min, max))
+}
+
+#[test]
+fn circles_intersect_when_their_radii_reach_each_other() {
+    assert!(circle(0.0, 0.0, 5.0).intersects(&circle(8.0, 0.0, 3.0)));
+    assert!(circle(0.0, 0.0, 5.0).intersects(&circle(1.0, 1.0, 1.0)));
+    assert!(!circle(0.0, 0.0, 5.0).intersects(&circle(8.0, 0.1, 3.0)));
+}
+
+#[test]
+fn rects_intersect_when_they_overlap_or_touch() {
+    let a = rect(Vec2::ZERO, Vec2::new(10.0, 10.0));
+    assert!(a.intersects(&rect(Vec2::new(5.0, 5.0), Vec2::new(15.0, 15.0))));
+    assert!(a.intersects(&rect(Vec2::new(10.0, 0.0), Vec2::new(20.0, 10.0))));
+    assert!(a.intersects(&rect(Vec2::new(2.0, 2.0), Vec2::new(3.0, 3.0))));
+    assert!(!a.intersects(&rect(Vec2::new(10.5, 0.0), Vec2::new(20.0, 10.0))));
+}
+
+#[test]
+fn circles_and_rects_intersect_in_either_order() {
+    let square = rect(Vec2::ZERO, Vec2::new(10.0, 10.0));
+    let cases = [
+        // Touching an edge
+        (circle(13.0, 5.0, 3.0), true),
+        // Center inside the rect
+        (circle(5.0, 5.0, 1.0), true),
+        // Inside the rect's bounding box grown by the radius, but past the corner
+        (circle(12.5, 12.5, 3.0), false),
+        (circle(12.0, 12.0, 3.0), true),
+        (circle(-4.0, 5.0, 3.0), false),
+    ];
+    for (circle, expected) in cases {
+        assert_eq!(circle.intersects(&square), expected, "{circle:?}");
+        assert_eq!(square.intersects(&circle), expected, "{circle:?}");
+    }
+}
+
+#[test]
+fn hitboxes_are_placed_with_the_transform_scale() {
+    let transform = Transform::from_xyz(100.0, 50.0, 0.0).with_scale(Vec3::splat(3.0));
+
+    assert_eq!(
+        Hitbox::circle(8.0)
+            .with_offset(Vec2::new(0.0, -4.0))
+            .place(&transform),
+        circle(100.0, 38.0, 24.0)
+    );
+    assert_eq!(
+        Hitbox::rect(Vec2::new(10.0, 4.0)).place(&transform),
+        rect(Vec2::new(85.0, 44.0), Vec2::new(115.0, 56.0))
+    );
+}
+
+#[test]
+fn layers_only_interact_across_sides() {
+    use CollisionLayer::*;
+    let interacting = [
+        (Player, Enemy),
+        (Player, EnemyProjectile),
+        (Player, Pickup),
+        (Enemy, PlayerProjectile),
+    ];
+
+    for a in LAYERS {
+        for b in LAYERS {
+            let expected = interacting.contains(&(a, b)) || interacting.contains(&(b, a));
+            assert_eq!(a.interacts_with(b), expected, "{a:?} and {b:?}");
+        }
+    }
+}
+
+fn spawn_collider(app: &mut App, position: Vec2, layer: CollisionLayer) -> Entity {
+    let entity = match layer {
+        CollisionLayer::Player => common::spawn_player(app, position),
+        CollisionLayer::Enemy => common::spawn_enemy(app, position),
+        _ => app
+            .world
+            .spawn(Transform::from_translation(position.extend(0.0)))
+            .id(),
+    };
+    app.world
+        .entity_mut(entity)
+        .insert((Hitbox::circle(5.0), layer));
+    entity
+}
+
+/// Runs one simulation tick and returns the events of type `E` it sent.
+fn tick<E: Event + Clone>(app: &mut App) -> Vec<E> {
+    let mut reader = ManualEventReader::<E>::default();
+    reader.clear(app.world.resource::<Events<E>>());
+    app.update();
+    reader
+        .read(app.world.resource::<Events<E>>())
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn one_overlapping_enemy_sends_one_player_enemy_event() {
+    let mut app = common::simulation_app(SpatialBackend::SpatialHash, CollisionPlugin);
+    let player = spawn_collider(&mut app, Vec2::ZERO, CollisionLayer::Player);
+    let enemy = spawn_collider(&mut app, Vec2::new(8.0, 0.0), CollisionLayer::Enemy);
+    spawn_collider(&mut app, Vec2::new(40.0, 0.0), CollisionLayer::Enemy);
+    spawn_collider(
+        &mut app,
+        Vec2::new(-8.0, 0.0),
+        CollisionLayer::PlayerProjectile,
+    );
+
+    assert_eq!(
+        tick::<PlayerEnemyCollisionEvent>(&mut app),
+        [PlayerEnemyCollisionEvent { player, enemy }]
+    );
+}
+
+#[test]
+fn contact_events_name_each_entity_by_its_role() {
+    let mut app = common::simulation_app(SpatialBackend::SpatialHash, CollisionPlugin);
+    // Spawned before the entities they touch, so pairs are found the other way round
+    let pickup = spawn_collider(&mut app, Vec2::new(0.0, 8.0), CollisionLayer::Pickup);
+    let enemy_projectile = spawn_collider(
+        &mut app,
+        Vec2::new(0.0, -8.0),
+        CollisionLayer::EnemyProjectile,
+    );
+    let player = spawn_collider(&mut app, Vec2::ZERO, CollisionLayer::Player);
+    let enemy = spawn_collider(&mut app, Vec2::new(500.0, 0.0), CollisionLayer::Enemy);
+    let player_projectile = spawn_collider(
+        &mut app,
+        Vec2::new(508.0, 0.0),
+        CollisionLayer::PlayerProjectile,
+    );
+    app.update();
+
+    let mut projectile_hits = tick::<ProjectileHitEvent>(&mut app);
+    projectile_hits.sort_by_key(|hit| hit.projectile);
+    let mut expected = vec![
+        ProjectileHitEvent {
+            projectile: enemy_projectile,
+            target: player,
+        },
+        ProjectileHitEvent {
+            projectile: player_projectile,
+            target: enemy,
+        },
+    ];
+    expected.sort_by_key(|hit| hit.projectile);
+    assert_eq!(projectile_hits, expected);
+
+    assert_eq!(
+        tick::<PickupContactEvent>(&mut app),
+        [PickupContactEvent { pickup, player }]
+    );
+}
@@ -0,0 +1,56 @@
+//! Fixtures shared by the integration tests that drive the simulation.
+
+use std::time::Duration;
+
+use bevy::app::Plugins;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+
+use eternal_gauntlet::enemy::{Enemy, EnemyStats};
+use eternal_gauntlet::player::{Health, Player};
+use eternal_gauntlet::simulation::{SimulationPlugin, SIMULATION_HZ};
+use eternal_gauntlet::spatial_index::{SpatialBackend, SpatialIndexPlugin};
+use eternal_gauntlet::state::GameState;
+
+/// An app running the simulation, the spatial index and `plugins`, already
+/// `InGame`. Every update runs one simulation tick. The simulation only ticks
+/// once a player is spawned.
+pub fn simulation_app<M>(backend: SpatialBackend, plugins: impl Plugins<M>) -> App {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, SimulationPlugin, SpatialIndexPlugin))
+        .add_plugins(plugins)
+        .init_state::<GameState>()
+        .insert_resource(backend)
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+            1.0 / SIMULATION_HZ,
+        )));
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::InGame);
+    app.update();
+    app
+}
+
+pub fn spawn_player(app: &mut App, position: Vec2) -> Entity {
+    app.world
+        .spawn((
+            Player,
+            Health(100.0),
+            Transform::from_translation(position.extend(0.0)),
+        ))
+        .id()
+}
+
+pub fn spawn_enemy(app: &mut App, position: Vec2) -> Entity {
+    let stats = EnemyStats {
+        health: 1,
+        damage: 1.0,
+        power: 1,
+    };
+    app.world
+        .spawn((
+            Enemy::new(stats),
+            Transform::from_translation(position.extend(0.0)),
+        ))
+        .id()
+}
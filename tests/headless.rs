@@ -0,0 +1,248 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use bevy::prelude::*;
+use eternal_gauntlet::enemy::{Enemy, EnemyLeash, RecycleStrategy, SpawnBudget};
+use eternal_gauntlet::headless::{
+    load_enemy_data, BotPolicy, BotView, HeadlessRun, IdleBot, KitingBot, TurretBot,
+};
+use eternal_gauntlet::player::{Player, PlayerInput, PlayerState};
+use eternal_gauntlet::simulation::SIMULATION_HZ;
+use eternal_gauntlet::spawn_timeline::SpawnTimeline;
+use eternal_gauntlet::state::GameState;
+use eternal_gauntlet::{
+    ENEMY_SPAWN_RING_MAX, ENEMY_SPAWN_RING_MIN, ENEMY_SPEED, PLAYER_DEATH_DURATION,
+};
+
+const SEED: u64 = 1234;
+
+fn assets_dir() -> &'static Path {
+    Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/assets"))
+}
+
+#[test]
+fn idle_wizard_dies_between_0_15_and_0_40() {
+    let mut run = HeadlessRun::new(assets_dir(), SEED, IdleBot).unwrap();
+    let summary = run.run_for(300.0);
+
+    assert!(summary.died);
+    assert!(
+        (15.0..=40.0).contains(&summary.time_survived),
+        "idle wizard died at {}s",
+        summary.time_survived
+    );
+    assert_eq!(summary.score, 0);
+}
+
+#[test]
+fn enemies_touching_the_player_hurt_it() {
+    let mut run = HeadlessRun::new(assets_dir(), SEED, IdleBot).unwrap();
+    let is_hurt = |run: &mut HeadlessRun| {
+        let world = run.world();
+        let state = world
+            .query_filtered::<&PlayerState, With<Player>>()
+            .single(world);
+        matches!(state, PlayerState::Hurt(_))
+    };
+
+    while !is_hurt(&mut run) {
+        run.step();
+        assert!(run.game_time() < 40.0, "no enemy reached the player");
+    }
+    // The hurt state wears off once enemies stop touching the player
+    let world = run.world();
+    let enemies: Vec<Entity> = world
+        .query_filtered::<Entity, With<Enemy>>()
+        .iter(world)
+        .collect();
+    for enemy in enemies {
+        world.despawn(enemy);
+    }
+    run.make_player_invulnerable();
+    for _ in 0..30 {
+        run.step();
+    }
+    assert!(!is_hurt(&mut run));
+}
+
+#[test]
+fn dying_players_play_their_death_clip_before_the_game_is_over() {
+    let mut run = HeadlessRun::new(assets_dir(), SEED, IdleBot).unwrap();
+    let player_state = |run: &mut HeadlessRun| {
+        let world = run.world();
+        let mut query = world.query_filtered::<&PlayerState, With<Player>>();
+        query.get_single(world).ok().copied()
+    };
+
+    while !matches!(player_state(&mut run), Some(PlayerState::Dead(_))) {
+        run.step();
+        assert!(run.game_time() < 40.0, "the idle player never died");
+    }
+    let died_at = run.game_time();
+    let mut ticks_dead: u32 = 0;
+    while run.state() == GameState::InGame {
+        run.step();
+        ticks_dead += 1;
+    }
+
+    assert_eq!(run.game_time(), died_at);
+    // Plus the update that applies the state change, and rounding of the countdown
+    let expected = (PLAYER_DEATH_DURATION * SIMULATION_HZ as f32).round() as u32;
+    assert!(
+        (expected..=expected + 2).contains(&ticks_dead),
+        "dead for {ticks_dead} ticks"
+    );
+    assert!(run.summary().died);
+}
+
+#[test]
+fn casting_outlives_idling() {
+    let idle = HeadlessRun::new(assets_dir(), SEED, IdleBot)
+        .unwrap()
+        .run_for(300.0);
+    let turret = HeadlessRun::new(assets_dir(), SEED, TurretBot)
+        .unwrap()
+        .run_for(300.0);
+
+    assert!(turret.score > 0);
+    assert!(turret.time_survived > idle.time_survived);
+}
+
+#[test]
+fn spawn_counts_match_enemy_spawns_json() {
+    const CHECK_AT: f32 = 50.5;
+
+    let mut run = HeadlessRun::new(assets_dir(), SEED, IdleBot).unwrap();
+    run.make_player_invulnerable();
+    let summary = run.run_for(CHECK_AT);
+    assert!(!summary.died);
+
+    // Spawns are checked every 0.1s, so each started entry may lag by one, even
+    // after its window closed between two checks.
+    let time = run.game_time();
+    let (_, spawn_data) = load_enemy_data(assets_dir()).unwrap();
+    let timeline = SpawnTimeline::new(&spawn_data);
+    let mut expected = BTreeMap::new();
+    let mut tolerance = BTreeMap::new();
+    for spawn in timeline.spawns() {
+        let count = timeline.planned_count(spawn, time);
+        *expected.entry(spawn.race.clone()).or_insert(0) += count;
+        if count > 0 {
+            *tolerance.entry(spawn.race.clone()).or_insert(0) += 1;
+        }
+    }
+
+    let counts = run.enemy_counts();
+    for (race, expected_count) in &expected {
+        let count = counts.get(race).copied().unwrap_or(0);
+        assert!(
+            count.abs_diff(*expected_count) <= tolerance.get(race).copied().unwrap_or(0),
+            "{race}: spawned {count}, expected about {expected_count}"
+        );
+    }
+}
+
+#[test]
+fn spawn_budget_recycles_enemies_at_the_cap() {
+    let run_with = |recycle| {
+        let mut run = HeadlessRun::new(assets_dir(), SEED, IdleBot).unwrap();
+        run.world().insert_resource(SpawnBudget {
+            max_enemies: 40,
+            recycle,
+        });
+        run.make_player_invulnerable();
+        run.run_for(50.5);
+        run
+    };
+    let mut recycled = run_with(RecycleStrategy::Farthest);
+    let mut dropped = run_with(RecycleStrategy::Drop);
+
+    assert_eq!(recycled.field_stats().enemies_alive, 40);
+    assert_eq!(dropped.field_stats().enemies_alive, 40);
+    // Recycled enemies take the race of the spawns that no longer fit
+    assert_ne!(recycled.enemy_counts(), dropped.enemy_counts());
+}
+
+/// Walks right forever, leaving the enemies behind it.
+struct WalkingBot;
+
+impl BotPolicy for WalkingBot {
+    fn decide(&mut self, _view: &BotView) -> PlayerInput {
+        PlayerInput {
+            movement: Vec2::X,
+            ..default()
+        }
+    }
+}
+
+#[test]
+fn leashed_enemies_return_to_the_spawn_ring() {
+    const LEASH_DISTANCE: f32 = ENEMY_SPAWN_RING_MAX + 500.0;
+
+    let mut run = HeadlessRun::new(assets_dir(), SEED, WalkingBot).unwrap();
+    run.world()
+        .insert_resource(EnemyLeash::new(LEASH_DISTANCE).unwrap());
+    run.make_player_invulnerable();
+
+    let mut enemies: HashMap<Entity, (Vec2, f32)> = HashMap::new();
+    let mut leashed = 0;
+    while run.game_time() < 40.0 {
+        run.step();
+        let world = run.world();
+        let player = world
+            .query_filtered::<&Transform, With<Player>>()
+            .single(world)
+            .translation
+            .truncate();
+        let mut query = world.query::<(Entity, &Transform, &Enemy)>();
+        for (entity, transform, enemy) in query.iter(world) {
+            let position = transform.translation.truncate();
+            let health = enemy.current_health;
+            if let Some((previous_position, previous_health)) =
+                enemies.insert(entity, (position, health))
+            {
+                // Walking enemies cover about one unit per tick
+                if previous_position.distance(position) < ENEMY_SPEED {
+                    continue;
+                }
+                leashed += 1;
+                let distance = position.distance(player);
+                assert!(
+                    (ENEMY_SPAWN_RING_MIN..=ENEMY_SPAWN_RING_MAX).contains(&distance),
+                    "leashed enemy landed {distance} from the player"
+                );
+                assert_eq!(health, previous_health);
+            }
+        }
+    }
+
+    assert!(leashed > 0);
+    assert_eq!(run.field_stats().enemies_leashed, leashed);
+}
+
+#[test]
+fn sped_up_runs_tick_as_often_as_asked() {
+    let mut run = HeadlessRun::new(assets_dir(), SEED, IdleBot).unwrap();
+    let start = run.game_time();
+    run.set_speed(60);
+    run.step();
+
+    // The fixed timestep may carry part of a tick over from the last update.
+    assert!(
+        (run.game_time() - start - 1.0).abs() <= 1.01 / SIMULATION_HZ as f32,
+        "one step at 60 ticks per update ran {}s",
+        run.game_time() - start
+    );
+}
+
+#[test]
+fn same_seed_and_bot_reproduce_the_run() {
+    let first = HeadlessRun::new(assets_dir(), SEED, KitingBot)
+        .unwrap()
+        .run_for(90.0);
+    let second = HeadlessRun::new(assets_dir(), SEED, KitingBot)
+        .unwrap()
+        .run_for(90.0);
+
+    assert_eq!(first, second);
+}
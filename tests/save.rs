@@ -0,0 +1,64 @@
+use eternal_gauntlet::meta_progression::{MetaProgression, MetaUpgrade};
+use eternal_gauntlet::save::{SaveData, SAVE_VERSION};
+
+const V1_SAVE: &str = r#"{
+    "version": 1,
+    "runs_played": 2,
+    "high_scores": {
+        "wizzard-m": [
+            { "run_number": 2, "hero": "wizzard-m", "score": 120, "time_survived": 95.5, "level": 4, "kills": 30 }
+        ]
+    },
+    "history": [
+        { "run_number": 2, "hero": "wizzard-m", "score": 120, "time_survived": 95.5, "level": 4, "kills": 30 },
+        { "run_number": 1, "hero": "wizzard-m", "score": 0, "time_survived": 12.0, "level": 1, "kills": 0 }
+    ]
+}"#;
+
+#[test]
+fn v1_saves_migrate_to_the_current_version() {
+    let save_data = SaveData::from_json(V1_SAVE).unwrap();
+
+    assert_eq!(save_data.version, SAVE_VERSION);
+    assert_eq!(save_data.runs_played, 2);
+    assert_eq!(save_data.history.len(), 2);
+    assert_eq!(save_data.high_scores("wizzard-m")[0].score, 120);
+    assert_eq!(save_data.high_scores("wizzard-m")[0].currency_earned, 0);
+    assert_eq!(save_data.meta, MetaProgression::default());
+    assert!(!save_data.read_only);
+}
+
+#[test]
+fn saves_from_a_newer_build_are_rejected() {
+    let newer = format!(
+        r#"{{ "version": {}, "runs_played": 0, "high_scores": {{}}, "history": [] }}"#,
+        SAVE_VERSION + 1
+    );
+
+    assert!(SaveData::from_json(&newer).is_err());
+    assert_eq!(SaveData::stored_version(&newer), Some(SAVE_VERSION + 1));
+}
+
+#[test]
+fn corrupt_saves_are_rejected() {
+    assert!(SaveData::from_json("{ \"version\": 2, \"runs_").is_err());
+    assert!(SaveData::from_json(r#"{ "runs_played": 3 }"#).is_err());
+    assert!(SaveData::from_json(r#"{ "version": 0 }"#).is_err());
+    assert_eq!(SaveData::stored_version("not json"), None);
+}
+
+#[test]
+fn current_saves_round_trip() {
+    let mut save_data = SaveData::from_json(V1_SAVE).unwrap();
+    save_data.meta.currency = 500;
+    assert!(save_data.meta.purchase(MetaUpgrade::ExperienceGain));
+
+    let loaded = SaveData::from_json(&save_data.to_json().unwrap()).unwrap();
+    assert_eq!(loaded.meta, save_data.meta);
+    assert_eq!(
+        loaded.meta.levels().into_iter().collect::<Vec<_>>(),
+        [("experience_gain".to_string(), 1)]
+    );
+    assert_eq!(loaded.history, save_data.history);
+    assert_eq!(loaded.high_scores, save_data.high_scores);
+}
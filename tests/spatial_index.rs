@@ -0,0 +1,180 @@
+mod common;
+
+use std::collections::BTreeSet;
+use std::time::Duration;
+
+use bevy::ecs::system::SystemState;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+
+use eternal_gauntlet::enemy::{leash_enemies, EnemyLeash};
+use eternal_gauntlet::simulation::{InterpolatedTransform, SimSet, SIMULATION_HZ};
+use eternal_gauntlet::spatial_index::{
+    EnemyKdTree, SpatialBackend, SpatialHash, SpatialIndex, SpatialStore,
+};
+use eternal_gauntlet::state::GameState;
+use eternal_gauntlet::{ENEMY_LEASH_DISTANCE, SPATIAL_HASH_CELL_SIZE};
+
+fn enemies() -> Vec<(Entity, Vec2)> {
+    let mut rng = ChaCha8Rng::seed_from_u64(7);
+    (0..500)
+        .map(|index| {
+            let position = Vec2::new(rng.gen_range(-800.0..800.0), rng.gen_range(-800.0..800.0));
+            (Entity::from_raw(index), position)
+        })
+        .collect()
+}
+
+fn sorted(entities: Vec<Entity>) -> BTreeSet<Entity> {
+    entities.into_iter().collect()
+}
+
+#[test]
+fn kd_tree_and_spatial_hash_find_the_same_enemies() {
+    let mut enemies = enemies();
+    let mut hash = SpatialHash::new(SPATIAL_HASH_CELL_SIZE);
+    for (entity, position) in enemies.iter() {
+        hash.insert(*entity, *position);
+    }
+    // Move some enemies across cells and drop others, as the update system would
+    for (entity, position) in enemies.iter_mut().step_by(3) {
+        *position += Vec2::new(170.0, -230.0);
+        hash.insert(*entity, *position);
+    }
+    for (entity, _) in enemies.drain(..50) {
+        hash.remove(entity);
+    }
+    assert_eq!(hash.len(), enemies.len());
+
+    let tree = EnemyKdTree::build(enemies.iter().copied());
+    for (center, radius) in [(Vec2::ZERO, 50.0), (Vec2::new(300.0, -120.0), 400.0)] {
+        let expected: BTreeSet<Entity> = enemies
+            .iter()
+            .filter(|(_, position)| position.distance(center) <= radius)
+            .map(|(entity, _)| *entity)
+            .collect();
+        assert_eq!(sorted(tree.within_radius(center, radius)), expected);
+        assert_eq!(sorted(hash.within_radius(center, radius)), expected);
+    }
+
+    let (min, max) = (Vec2::new(-200.0, 0.0), Vec2::new(450.0, 90.0));
+    assert_eq!(
+        sorted(tree.within_rect(min, max)),
+        sorted(hash.within_rect(min, max))
+    );
+
+    let target = Vec2::new(-420.0, 610.0);
+    let mut by_distance = enemies.clone();
+    by_distance.sort_by(|a, b| a.1.distance(target).total_cmp(&b.1.distance(target)));
+    let expected: Vec<Entity> = by_distance.iter().take(5).map(|(e, _)| *e).collect();
+    assert_eq!(hash.nearest(target, 5), expected);
+    assert_eq!(sorted(tree.nearest(target, 5)), sorted(expected));
+}
+
+/// An app running only the simulation and spatial index, with a player at the origin.
+fn spatial_app(backend: SpatialBackend) -> App {
+    let mut app = common::simulation_app(backend, ());
+    common::spawn_player(&mut app, Vec2::ZERO);
+    app
+}
+
+#[test]
+fn spatial_hash_drops_despawned_enemies_between_ticks_and_runs() {
+    let mut app = spatial_app(SpatialBackend::SpatialHash);
+    let enemies: Vec<Entity> = (0..10)
+        .map(|i| common::spawn_enemy(&mut app, Vec2::new(i as f32 * 40.0, 0.0)))
+        .collect();
+    app.update();
+    assert_eq!(app.world.resource::<SpatialHash>().len(), 10);
+
+    // At a high frame rate several frames pass without a simulation tick.
+    app.insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(
+        0.1 / SIMULATION_HZ,
+    )));
+    app.world.despawn(enemies[0]);
+    for _ in 0..5 {
+        app.update();
+    }
+    assert_eq!(app.world.resource::<SpatialHash>().len(), 9);
+
+    // Leaving the run drops the rest, even though the simulation stops ticking.
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::GameOver);
+    for entity in &enemies[1..] {
+        app.world.despawn(*entity);
+    }
+    app.update();
+    app.update();
+    assert!(app.world.resource::<SpatialHash>().is_empty());
+}
+
+#[test]
+fn nearest_skips_enemies_despawned_since_the_kd_tree_was_built() {
+    let mut app = spatial_app(SpatialBackend::KdTree);
+    let enemies: Vec<Entity> = [10.0, 20.0, 24.0, 40.0, 50.0]
+        .into_iter()
+        .map(|x| common::spawn_enemy(&mut app, Vec2::new(x, 0.0)))
+        .collect();
+    for _ in 0..10 {
+        app.update();
+    }
+
+    // The wand just killed the nearest enemy and another one stepped closer.
+    app.world.despawn(enemies[0]);
+    app.world
+        .get_mut::<Transform>(enemies[2])
+        .unwrap()
+        .translation
+        .x = 19.0;
+
+    let mut state = SystemState::<SpatialIndex>::new(&mut app.world);
+    let index = state.get(&app.world);
+    assert_eq!(
+        index.nearest(Vec2::ZERO).map(|hit| hit.entity),
+        Some(enemies[2])
+    );
+    let nearest: Vec<Entity> = index
+        .k_nearest(Vec2::ZERO, 4)
+        .into_iter()
+        .map(|hit| hit.entity)
+        .collect();
+    assert_eq!(nearest, [enemies[2], enemies[1], enemies[3], enemies[4]]);
+    assert_eq!(index.k_nearest(Vec2::ZERO, 10).len(), 4);
+}
+
+#[test]
+fn leashed_enemies_are_found_before_the_next_kd_tree_refresh() {
+    let mut app = spatial_app(SpatialBackend::KdTree);
+    app.init_resource::<EnemyLeash>()
+        .add_systems(FixedUpdate, leash_enemies.in_set(SimSet::Movement));
+    let enemy = common::spawn_enemy(&mut app, Vec2::new(100.0, 0.0));
+    app.world
+        .entity_mut(enemy)
+        .insert(InterpolatedTransform::default());
+    // The tree refreshes every 6 ticks, the first time around now.
+    for _ in 0..7 {
+        app.update();
+    }
+
+    app.world.get_mut::<Transform>(enemy).unwrap().translation.x = ENEMY_LEASH_DISTANCE + 100.0;
+    app.update();
+
+    let position = app
+        .world
+        .get::<Transform>(enemy)
+        .unwrap()
+        .translation
+        .truncate();
+    assert!(position.length() < ENEMY_LEASH_DISTANCE);
+    let mut state = SystemState::<SpatialIndex>::new(&mut app.world);
+    let index = state.get(&app.world);
+    let hits: Vec<Entity> = index
+        .within_radius(position, 1.0)
+        .into_iter()
+        .map(|hit| hit.entity)
+        .collect();
+    assert_eq!(hits, [enemy]);
+}
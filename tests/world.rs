@@ -0,0 +1,31 @@
+use bevy::prelude::*;
+
+use eternal_gauntlet::hero_registry::HeroRegistry;
+use eternal_gauntlet::player::Player;
+use eternal_gauntlet::resources::RunStats;
+use eternal_gauntlet::state::GameState;
+use eternal_gauntlet::world::{GameEntity, RunHero, WorldPlugin};
+
+#[test]
+fn runs_without_a_spawnable_hero_return_to_the_menu_before_spawning_anything() {
+    let mut app = App::new();
+    app.add_plugins((MinimalPlugins, WorldPlugin))
+        .init_state::<GameState>()
+        .init_resource::<HeroRegistry>()
+        .init_resource::<RunStats>();
+    app.world
+        .resource_mut::<NextState<GameState>>()
+        .set(GameState::GameInit);
+    app.update();
+    app.update();
+
+    assert_eq!(
+        *app.world.resource::<State<GameState>>().get(),
+        GameState::MainMenu
+    );
+    assert!(!app.world.contains_resource::<RunHero>());
+    let mut game_entities = app
+        .world
+        .query_filtered::<Entity, Or<(With<GameEntity>, With<Player>)>>();
+    assert_eq!(game_entities.iter(&app.world).count(), 0);
+}